@@ -0,0 +1,67 @@
+//! Measures allocations and time spent assembling properties for `track` calls.
+//!
+//! The tracker is disabled, so each call merges properties and builds the payload, but
+//! never hits the network. Run with `cargo bench --bench properties`.
+
+use openpanel_sdk::sdk::Tracker;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+const ITERATIONS: usize = 10_000;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let globals = (0..20)
+        .map(|i| (format!("global_{}", i), "value".to_string()))
+        .collect::<HashMap<_, _>>();
+    let tracker = Tracker::new(
+        "http://localhost/track".to_string(),
+        "id".to_string(),
+        "secret".to_string(),
+    )
+    .with_global_properties(globals)
+    .disable();
+    let properties = HashMap::from([("name".to_string(), "rust".to_string())]);
+
+    for (name, properties) in [("no properties", None), ("properties", Some(properties))] {
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let started = Instant::now();
+
+        for _ in 0..ITERATIONS {
+            let properties = properties.clone();
+            let _ = runtime.block_on(tracker.track("bench".to_string(), None, properties, None));
+        }
+
+        let elapsed = started.elapsed();
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+
+        println!(
+            "{:<16} {:>8.1} allocations/call {:>10.2?}/call",
+            name,
+            allocations as f64 / ITERATIONS as f64,
+            elapsed / ITERATIONS as u32
+        );
+    }
+}
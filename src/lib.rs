@@ -17,6 +17,8 @@ pub enum TrackerError {
     TooManyRequests,
     #[error("Internal error")]
     Internal,
+    #[error("Unexpected response status: {0}")]
+    UnexpectedStatus(u16),
     #[error("Request error: {0:?}")]
     Request(#[from] reqwest::Error),
     #[error("Error serializing payload: {0:?}")]
@@ -25,8 +27,182 @@ pub enum TrackerError {
     HeaderName(#[from] reqwest::header::InvalidHeaderName),
     #[error("Invalid header value: {0:?}")]
     HeaderValue(#[from] reqwest::header::InvalidHeaderValue),
+    #[error("Unknown property: {0}")]
+    UnknownProperty(String),
+    #[error("Unsupported property value: {0}")]
+    UnsupportedPropertyValue(String),
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
     #[error("Tracker is disabled")]
     Disabled,
-    #[error("Event filtered")]
-    Filtered,
+    #[error("Event filtered: {reason}")]
+    Filtered { reason: String },
+    #[error("Event sampled out")]
+    Sampled,
+    #[error("Profile unchanged since the last identify")]
+    Unchanged,
+    #[error("Request timed out")]
+    Timeout,
+    #[error("Event sender is closed")]
+    SenderClosed,
+    #[error("Event queue is full")]
+    QueueFull,
+    #[error("Transport error: {1}")]
+    Transport(TransportErrorKind, String),
+    #[error("Validation failed: {}", list_issues(.0))]
+    Validation(Vec<ValidationIssue>),
+    #[error("IO error: {0:?}")]
+    Io(#[from] std::io::Error),
+    #[error("Amount out of the JS-safe integer range: {0}")]
+    AmountOutOfRange(i64),
+}
+
+/// Single problem found by a validation, e.g. `payload.name is missing`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Path of the invalid field, e.g. `payload.name` or `properties.plan`
+    pub field: String,
+    /// What's wrong with the field, e.g. `is missing`
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.field, self.message)
+    }
+}
+
+/// Enumerate the issues, e.g. `1) payload.name is missing, 2) payload.value must be an integer`
+fn list_issues(issues: &[ValidationIssue]) -> String {
+    issues
+        .iter()
+        .enumerate()
+        .map(|(i, issue)| format!("{}) {}", i + 1, issue))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Category of a transport error, see [TrackerError::transport_kind]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportErrorKind {
+    /// The connection couldn't be established, e.g. the host doesn't resolve or refuses
+    /// connections. Usually means OpenPanel is unreachable.
+    ConnectionFailed,
+    /// The request timed out
+    Timeout,
+    /// Any other transport error, e.g. an invalid request or a malformed response
+    Protocol,
+}
+
+impl TrackerError {
+    /// Category of the error if sending the request failed on the transport level, `None` for
+    /// other errors like HTTP status errors
+    pub fn transport_kind(&self) -> Option<TransportErrorKind> {
+        match self {
+            TrackerError::Request(e) if e.is_timeout() => Some(TransportErrorKind::Timeout),
+            TrackerError::Request(e) if e.is_connect() => {
+                Some(TransportErrorKind::ConnectionFailed)
+            }
+            TrackerError::Request(_) => Some(TransportErrorKind::Protocol),
+            TrackerError::Timeout => Some(TransportErrorKind::Timeout),
+            TrackerError::Transport(kind, _) => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Whether the event was intentionally not sent, i.e. it was filtered, sampled out, the
+    /// profile is unchanged or the tracker is disabled. These are not failures and usually
+    /// don't need to be logged.
+    pub fn is_intentional_skip(&self) -> bool {
+        matches!(
+            self,
+            TrackerError::Filtered { .. }
+                | TrackerError::Disabled
+                | TrackerError::Sampled
+                | TrackerError::Unchanged
+        )
+    }
+}
+
+/// Errors building requests or responses in a custom [sdk::transport::HttpTransport]
+impl From<http::Error> for TrackerError {
+    fn from(e: http::Error) -> Self {
+        TrackerError::Transport(TransportErrorKind::Protocol, e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_display_validation_issues() {
+        let error = TrackerError::Validation(vec![
+            ValidationIssue::new("payload.name", "is missing"),
+            ValidationIssue::new("properties.nmae", "is not allowed"),
+        ]);
+
+        assert_eq!(
+            error.to_string(),
+            "Validation failed: 1) payload.name is missing, 2) properties.nmae is not allowed"
+        );
+    }
+
+    #[test]
+    fn can_detect_intentional_skips() {
+        assert!(
+            TrackerError::Filtered {
+                reason: "debug build".to_string()
+            }
+            .is_intentional_skip()
+        );
+        assert!(TrackerError::Disabled.is_intentional_skip());
+        assert!(TrackerError::Sampled.is_intentional_skip());
+        assert!(TrackerError::Unchanged.is_intentional_skip());
+        assert!(!TrackerError::Timeout.is_intentional_skip());
+        assert!(!TrackerError::NotAuthorized.is_intentional_skip());
+    }
+
+    async fn request_error(url: &str) -> TrackerError {
+        reqwest::get(url).await.unwrap_err().into()
+    }
+
+    #[tokio::test]
+    async fn can_classify_transport_errors() {
+        assert_eq!(
+            request_error("http://127.0.0.1:9/track")
+                .await
+                .transport_kind(),
+            Some(TransportErrorKind::ConnectionFailed)
+        );
+        assert_eq!(
+            request_error("http://openpanel.invalid/track")
+                .await
+                .transport_kind(),
+            Some(TransportErrorKind::ConnectionFailed)
+        );
+        assert_eq!(
+            request_error("not a url").await.transport_kind(),
+            Some(TransportErrorKind::Protocol)
+        );
+        assert_eq!(
+            TrackerError::Timeout.transport_kind(),
+            Some(TransportErrorKind::Timeout)
+        );
+        assert_eq!(
+            TrackerError::Transport(TransportErrorKind::ConnectionFailed, "down".to_string())
+                .transport_kind(),
+            Some(TransportErrorKind::ConnectionFailed)
+        );
+        assert_eq!(TrackerError::Internal.transport_kind(), None);
+    }
 }
@@ -0,0 +1,45 @@
+//! Stable anonymous ids for events without a logged-in user
+
+use std::sync::Mutex;
+
+/// Storage of the anonymous id, set via [crate::sdk::Tracker::with_anonymous_id_store].
+/// Implement this to persist the id across restarts, e.g. in a file or the keychain.
+pub trait AnonymousIdStore: Send + Sync {
+    /// Previously saved anonymous id, if any
+    fn load(&self) -> Option<String>;
+    /// Save a newly generated anonymous id
+    fn save(&self, id: &str);
+}
+
+/// Store keeping the anonymous id in memory, used by default. The id is lost once the
+/// process exits.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    id: Mutex<Option<String>>,
+}
+
+impl AnonymousIdStore for MemoryStore {
+    fn load(&self) -> Option<String> {
+        self.id.lock().unwrap().clone()
+    }
+
+    fn save(&self, id: &str) {
+        *self.id.lock().unwrap() = Some(id.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_store_keeps_id() {
+        let store = MemoryStore::default();
+
+        assert_eq!(store.load(), None);
+
+        store.save("anon_123");
+
+        assert_eq!(store.load(), Some("anon_123".to_string()));
+    }
+}
@@ -0,0 +1,166 @@
+//! Fire-and-forget tracking from synchronous code via a background thread
+
+use crate::sdk::Tracker;
+use crate::sdk::sender::{FlushReport, TrackEvent};
+use crate::{TrackerError, TrackerResult};
+use std::thread::JoinHandle;
+use tokio::sync::{mpsc, oneshot};
+
+/// Default number of events queued by a [BackgroundTracker] before [BackgroundTracker::track]
+/// returns [TrackerError::QueueFull]
+pub const DEFAULT_BACKGROUND_CAPACITY: usize = 1024;
+
+/// Message sent to the background thread
+enum Message {
+    Event(TrackEvent),
+    Flush(oneshot::Sender<FlushReport>),
+}
+
+/// Tracker owning a background thread which sends the events, for apps without an async
+/// runtime. [BackgroundTracker::track] only enqueues the event and returns right away.
+///
+/// Dropping the tracker sends all queued events and waits for the thread to finish, so don't
+/// drop it on an async runtime's worker thread.
+#[derive(Debug)]
+pub struct BackgroundTracker {
+    tx: Option<mpsc::Sender<Message>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl BackgroundTracker {
+    /// Move the tracker into a background thread queueing up to
+    /// [DEFAULT_BACKGROUND_CAPACITY] events
+    pub fn new(tracker: Tracker) -> TrackerResult<Self> {
+        Self::with_capacity(tracker, DEFAULT_BACKGROUND_CAPACITY)
+    }
+
+    /// Move the tracker into a background thread queueing up to `capacity` events
+    pub fn with_capacity(tracker: Tracker, capacity: usize) -> TrackerResult<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        let thread = std::thread::Builder::new()
+            .name("openpanel-background".to_string())
+            .spawn(move || runtime.block_on(run(tracker, rx)))?;
+
+        Ok(Self {
+            tx: Some(tx),
+            thread: Some(thread),
+        })
+    }
+
+    /// Enqueue the event without blocking. Returns [TrackerError::QueueFull] if the queue is
+    /// full, dropping the event, and [TrackerError::SenderClosed] if the thread stopped.
+    pub fn track(&self, event: TrackEvent) -> TrackerResult<()> {
+        self.sender()?
+            .try_send(Message::Event(event))
+            .map_err(|e| match e {
+                mpsc::error::TrySendError::Full(_) => TrackerError::QueueFull,
+                mpsc::error::TrySendError::Closed(_) => TrackerError::SenderClosed,
+            })
+    }
+
+    /// Block until all events queued before this call are sent and return the counts of events
+    /// handled since the last report. Panics if called on an async runtime, like dropping the
+    /// tracker this call blocks the thread.
+    pub fn flush(&self) -> TrackerResult<FlushReport> {
+        let (tx, rx) = oneshot::channel();
+
+        self.sender()?
+            .blocking_send(Message::Flush(tx))
+            .map_err(|_| TrackerError::SenderClosed)?;
+
+        rx.blocking_recv().map_err(|_| TrackerError::SenderClosed)
+    }
+
+    fn sender(&self) -> TrackerResult<&mpsc::Sender<Message>> {
+        self.tx.as_ref().ok_or(TrackerError::SenderClosed)
+    }
+}
+
+impl Drop for BackgroundTracker {
+    fn drop(&mut self) {
+        // closing the channel lets the thread finish once the queue is drained
+        self.tx.take();
+
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                tracing::warn!("OpenPanel background thread panicked");
+            }
+        }
+    }
+}
+
+/// Send the queued events one after another until the channel is closed
+async fn run(tracker: Tracker, mut rx: mpsc::Receiver<Message>) {
+    let mut report = FlushReport::default();
+
+    // waiting asynchronously lets tasks spawned on the thread's runtime progress between events
+    while let Some(message) = rx.recv().await {
+        match message {
+            Message::Event(event) => {
+                match tracker
+                    .track_unfiltered(event.name, event.profile_id, event.properties)
+                    .await
+                {
+                    Ok(response) if response.status().is_success() => report.sent += 1,
+                    Ok(_) => report.failed += 1,
+                    Err(e) if e.is_intentional_skip() => report.dropped += 1,
+                    Err(e) => {
+                        tracing::warn!("Error sending event: {}", e);
+                        report.failed += 1;
+                    }
+                }
+            }
+            Message::Flush(reply) => {
+                // the caller may have stopped waiting for the report
+                let _ = reply.send(std::mem::take(&mut report));
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "disabled-at-compile-time")))]
+mod tests {
+    use super::*;
+    use crate::sdk::testing::MockTransport;
+
+    #[test]
+    fn can_flush_queued_events() -> anyhow::Result<()> {
+        let transport = MockTransport::new().with_response(500, "");
+        let tracker = BackgroundTracker::new(transport.tracker())?;
+
+        for i in 0..3 {
+            tracker.track(TrackEvent::new(format!("event_{}", i)))?;
+        }
+
+        assert_eq!(
+            tracker.flush()?,
+            FlushReport {
+                sent: 2,
+                failed: 1,
+                dropped: 0
+            }
+        );
+        assert_eq!(transport.events(), vec!["event_0", "event_1", "event_2"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sends_queued_events_on_drop() -> anyhow::Result<()> {
+        let transport = MockTransport::new();
+        let tracker = BackgroundTracker::with_capacity(transport.tracker(), 10)?;
+
+        for i in 0..5 {
+            tracker.track(TrackEvent::new(format!("event_{}", i)))?;
+        }
+
+        drop(tracker);
+
+        assert_eq!(transport.events().len(), 5);
+
+        Ok(())
+    }
+}
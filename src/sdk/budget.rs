@@ -0,0 +1,281 @@
+//! Gzip-compressed event buffer of the channel backed sender, capped by a memory budget
+
+use crate::TrackerResult;
+use crate::sdk::sender::{Priority, TrackEvent};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Reason of [crate::TrackerError::Filtered] for events dropped by
+/// [OverflowPolicy::DropNewest]
+pub const MEMORY_BUDGET_REASON: &str = "memory budget exceeded";
+
+/// Size of the gzip header, written before the first event
+const GZIP_HEADER_LEN: usize = 10;
+/// Size of the final deflate block and the gzip trailer, written when the batch is flushed
+const GZIP_FINISH_LEN: usize = 5 + 8;
+/// Size of a stored deflate block's header, also written by each sync flush
+const STORED_BLOCK_HEADER_LEN: usize = 5;
+/// Deflate block size assumed for the upper bound, smaller than any block emitted
+const MIN_BLOCK_LEN: usize = 16 * 1024;
+
+/// What the sender does with an event which doesn't fit into the memory budget set via
+/// [crate::sdk::sender::SenderConfig::with_memory_budget]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Send the buffered events to make room. While sending, the channel fills up and
+    /// [crate::sdk::sender::EventSender::send] waits, i.e. the callers are slowed down.
+    #[default]
+    Flush,
+    /// Drop the event, reported as dropped with the reason [MEMORY_BUDGET_REASON]
+    DropNewest,
+}
+
+/// Event as buffered, one JSON document per event
+#[derive(Serialize, Deserialize)]
+struct BufferedEvent {
+    id: u64,
+    name: String,
+    profile_id: Option<String>,
+    properties: Option<HashMap<String, String>>,
+    priority: u8,
+}
+
+/// Event serialized for the buffer, see [CompressedBuffer::push]
+pub(crate) struct EncodedEvent {
+    bytes: Vec<u8>,
+    priority: Priority,
+}
+
+impl EncodedEvent {
+    pub(crate) fn new(id: u64, event: &TrackEvent) -> TrackerResult<Self> {
+        let buffered = BufferedEvent {
+            id,
+            name: event.name.clone(),
+            profile_id: event.profile_id.clone(),
+            properties: event.properties.clone(),
+            priority: match event.priority {
+                Priority::Low => 0,
+                Priority::Normal => 1,
+                Priority::High => 2,
+            },
+        };
+
+        Ok(Self {
+            bytes: serde_json::to_vec(&buffered)?,
+            priority: event.priority,
+        })
+    }
+}
+
+/// Buffered events, compressed as a single gzip stream so events share the dictionary of
+/// repeated keys and values
+pub(crate) struct CompressedBuffer {
+    budget: usize,
+    pub(crate) policy: OverflowPolicy,
+    encoder: GzEncoder<Vec<u8>>,
+    len: usize,
+    ready: bool,
+}
+
+impl CompressedBuffer {
+    pub(crate) fn new(budget: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            budget,
+            policy,
+            encoder: GzEncoder::new(Vec::new(), Compression::default()),
+            len: 0,
+            ready: false,
+        }
+    }
+
+    /// Number of buffered events
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether a normal priority event is buffered, see [Priority::Low]
+    pub(crate) fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    /// Compressed size of the buffered events in bytes
+    pub(crate) fn size(&self) -> usize {
+        self.encoder.get_ref().len()
+    }
+
+    /// Whether the event fits into the budget. Deflate stores incompressible data as is, so
+    /// the uncompressed size plus the block headers, the sync flush and the gzip framing is
+    /// an upper bound, and the budget is never exceeded.
+    pub(crate) fn fits(&self, event: &EncodedEvent) -> bool {
+        let len = event.bytes.len();
+        let header = if self.len == 0 { GZIP_HEADER_LEN } else { 0 };
+        let bound = len + STORED_BLOCK_HEADER_LEN * (len / MIN_BLOCK_LEN + 2);
+
+        self.size().saturating_add(header + bound + GZIP_FINISH_LEN) <= self.budget
+    }
+
+    /// Append the event to the buffer
+    pub(crate) fn push(&mut self, event: EncodedEvent) -> TrackerResult<()> {
+        self.encoder.write_all(&event.bytes)?;
+        // emit the compressed bytes right away, so the size of the buffer is accurate
+        self.encoder.flush()?;
+        self.len += 1;
+        self.ready |= event.priority > Priority::Low;
+
+        Ok(())
+    }
+
+    /// Finish the gzip stream and reset the buffer
+    fn finish(&mut self) -> TrackerResult<Vec<u8>> {
+        let encoder = std::mem::replace(
+            &mut self.encoder,
+            GzEncoder::new(Vec::new(), Compression::default()),
+        );
+
+        self.len = 0;
+        self.ready = false;
+
+        Ok(encoder.finish()?)
+    }
+
+    /// Decompress and remove all buffered events
+    pub(crate) fn take(&mut self) -> TrackerResult<Vec<(u64, TrackEvent)>> {
+        let compressed = self.finish()?;
+
+        serde_json::Deserializer::from_reader(GzDecoder::new(compressed.as_slice()))
+            .into_iter::<BufferedEvent>()
+            .map(|buffered| {
+                let buffered = buffered?;
+                let priority = match buffered.priority {
+                    0 => Priority::Low,
+                    2 => Priority::High,
+                    _ => Priority::Normal,
+                };
+
+                Ok((
+                    buffered.id,
+                    TrackEvent {
+                        name: buffered.name,
+                        profile_id: buffered.profile_id,
+                        properties: buffered.properties,
+                        priority,
+                    },
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(i: usize) -> TrackEvent {
+        TrackEvent::new("page_view".to_string())
+            .with_profile_id("rust_123".to_string())
+            .with_properties(HashMap::from([
+                ("path".to_string(), format!("/articles/{}", i)),
+                ("referrer".to_string(), "https://example.com".to_string()),
+            ]))
+    }
+
+    #[test]
+    fn restores_buffered_events() -> anyhow::Result<()> {
+        let mut buffer = CompressedBuffer::new(4096, OverflowPolicy::Flush);
+
+        buffer.push(EncodedEvent::new(
+            7,
+            &event(1).with_priority(Priority::Low),
+        )?)?;
+
+        assert!(!buffer.is_ready());
+
+        buffer.push(EncodedEvent::new(8, &event(2))?)?;
+
+        assert!(buffer.is_ready());
+
+        let events = buffer.take()?;
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].0, 7);
+        assert_eq!(events[0].1.priority, Priority::Low);
+        assert_eq!(events[1].0, 8);
+        assert_eq!(events[1].1.name, "page_view");
+        assert_eq!(events[1].1.profile_id.as_deref(), Some("rust_123"));
+        assert_eq!(
+            events[1].1.properties.as_ref().unwrap()["path"],
+            "/articles/2"
+        );
+        assert_eq!(buffer.len(), 0);
+        assert!(buffer.take()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn compresses_similar_events() -> anyhow::Result<()> {
+        let mut buffer = CompressedBuffer::new(usize::MAX, OverflowPolicy::Flush);
+        let mut uncompressed = 0;
+
+        for i in 0..100 {
+            let encoded = EncodedEvent::new(i as u64, &event(i))?;
+
+            uncompressed += encoded.bytes.len();
+            buffer.push(encoded)?;
+        }
+
+        assert!(buffer.size() * 3 < uncompressed);
+
+        Ok(())
+    }
+
+    /// Fill a buffer of `budget` bytes, returning the number of events and the finished stream
+    fn fill(
+        budget: usize,
+        event: impl Fn(usize) -> TrackEvent,
+    ) -> anyhow::Result<(usize, Vec<u8>)> {
+        let mut buffer = CompressedBuffer::new(budget, OverflowPolicy::Flush);
+
+        loop {
+            let encoded = EncodedEvent::new(buffer.len() as u64, &event(buffer.len()))?;
+
+            if !buffer.fits(&encoded) {
+                break;
+            }
+
+            buffer.push(encoded)?;
+        }
+
+        let len = buffer.len();
+
+        Ok((len, buffer.finish()?))
+    }
+
+    #[test]
+    fn never_exceeds_budget() -> anyhow::Result<()> {
+        let (len, compressed) = fill(512, event)?;
+
+        assert!(len > 0);
+        assert!(compressed.len() <= 512);
+
+        // random values don't compress, so deflate stores them
+        let random = |_| {
+            TrackEvent::new("page_view".to_string()).with_properties(HashMap::from([(
+                "token".to_string(),
+                (0..4096)
+                    .map(|_| char::from(rand::random_range(b'!'..=b'~')))
+                    .collect(),
+            )]))
+        };
+        let (len, compressed) = fill(64 * 1024, random)?;
+
+        assert!(len > 0);
+        assert!(compressed.len() <= 64 * 1024);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Builder for trackers, inert by default
+
+use crate::TrackerResult;
+use crate::sdk::Tracker;
+use std::sync::Arc;
+
+/// API URL of the inert default builder. The `.invalid` TLD never resolves.
+pub const INERT_API_URL: &str = "http://openpanel.invalid/track";
+
+/// Builder for a [Tracker].
+///
+/// The default builder is inert: it produces a disabled tracker pointing at [INERT_API_URL]
+/// which never hits the network, so instrumented code can be exercised in tests with zero
+/// setup. Set credentials via [TrackerBuilder::with_credentials] or call
+/// [TrackerBuilder::enable] to build a live tracker.
+#[derive(Debug, Clone)]
+pub struct TrackerBuilder {
+    api_url: String,
+    client_id: String,
+    client_secret: String,
+    enabled: bool,
+}
+
+impl Default for TrackerBuilder {
+    fn default() -> Self {
+        Self {
+            api_url: INERT_API_URL.to_string(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            enabled: false,
+        }
+    }
+}
+
+impl TrackerBuilder {
+    /// Set the API URL events are sent to
+    pub fn with_api_url(mut self, api_url: String) -> Self {
+        self.api_url = api_url;
+        self
+    }
+
+    /// Set the client credentials and enable sending events
+    pub fn with_credentials(mut self, client_id: String, client_secret: String) -> Self {
+        self.client_id = client_id;
+        self.client_secret = client_secret;
+        self.enabled = true;
+        self
+    }
+
+    /// Enable sending events
+    pub fn enable(mut self) -> Self {
+        self.enabled = true;
+        self
+    }
+
+    /// Build the tracker with the default headers set
+    pub fn build(self) -> TrackerResult<Tracker> {
+        let tracker = Tracker::try_new(self.api_url, self.client_id, self.client_secret)?
+            .with_default_headers()?;
+
+        Ok(if self.enabled {
+            tracker
+        } else {
+            tracker.disable()
+        })
+    }
+
+    /// Build the tracker and wrap it in an [Arc], see [Tracker::build_shared]
+    pub fn build_shared(self) -> TrackerResult<Arc<Tracker>> {
+        self.build().map(Tracker::build_shared)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TrackerError;
+
+    #[tokio::test]
+    async fn default_builder_is_inert() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default().build()?;
+        let result = tracker.track("test".to_string(), None, None, None).await;
+
+        assert_eq!(tracker.api_url(), INERT_API_URL);
+        assert!(matches!(result, Err(TrackerError::Disabled)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn can_build_shared_tracker() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default().build_shared()?;
+        let result = tracker
+            .track_detached("test".to_string(), None, None)
+            .await?;
+
+        assert!(matches!(result, Err(TrackerError::Disabled)));
+        // the detached task released its clone
+        assert_eq!(Arc::strong_count(&tracker), 1);
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[test]
+    fn credentials_enable_tracker() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default()
+            .with_api_url("https://api.openpanel.dev/track".to_string())
+            .with_credentials("id".to_string(), "secret".to_string())
+            .build()?;
+
+        assert!(tracker.config_summary().contains("disabled=false"));
+        assert_eq!(tracker.client_id(), "id");
+
+        Ok(())
+    }
+}
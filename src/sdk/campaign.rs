@@ -0,0 +1,53 @@
+//! Campaign attribution used for UTM tracking
+
+use std::collections::HashMap;
+
+/// UTM campaign parameters attached to tracked events.
+/// Fields set to `None` are omitted from the payload.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Campaign {
+    pub source: Option<String>,
+    pub medium: Option<String>,
+    pub campaign: Option<String>,
+    pub term: Option<String>,
+    pub content: Option<String>,
+}
+
+impl From<Campaign> for HashMap<String, String> {
+    /// Convert campaign into properties using the canonical `utm_*` key names
+    fn from(campaign: Campaign) -> Self {
+        [
+            ("utm_source", campaign.source),
+            ("utm_medium", campaign.medium),
+            ("utm_campaign", campaign.campaign),
+            ("utm_term", campaign.term),
+            ("utm_content", campaign.content),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key.to_string(), value)))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_convert_campaign_into_properties() {
+        let campaign = Campaign {
+            source: Some("newsletter".to_string()),
+            medium: Some("email".to_string()),
+            ..Default::default()
+        };
+        let properties: HashMap<String, String> = campaign.into();
+
+        assert_eq!(
+            properties,
+            HashMap::from([
+                ("utm_source".to_string(), "newsletter".to_string()),
+                ("utm_medium".to_string(), "email".to_string()),
+            ])
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Source of the current time, replaceable to test time based features
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Source of the current time used by the SDK, e.g. for replay TTLs and timers.
+/// Set via [crate::sdk::Tracker::with_clock].
+pub trait Clock: Debug + Send + Sync {
+    /// Current point in time
+    fn now(&self) -> Instant;
+
+    /// Current wall clock time, e.g. to check explicit event timestamps
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Clock reading the system's monotonic time, used by default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clock which only moves forward when advanced manually. Clones share the same time,
+/// so keep a clone to advance the clock passed to the tracker.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    start: Instant,
+    start_system: SystemTime,
+    offset: Arc<Mutex<Duration>>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            start_system: SystemTime::now(),
+            offset: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+}
+
+impl ManualClock {
+    /// Move the clock forward by the given duration
+    pub fn advance(&self, duration: Duration) {
+        *self.offset.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + *self.offset.lock().unwrap()
+    }
+
+    fn system_time(&self) -> SystemTime {
+        self.start_system + *self.offset.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_advance_manual_clock() -> anyhow::Result<()> {
+        let clock = ManualClock::default();
+        let handle = clock.clone();
+        let started = clock.now();
+
+        assert_eq!(clock.now(), started);
+
+        handle.advance(Duration::from_secs(60));
+
+        assert_eq!(clock.now() - started, Duration::from_secs(60));
+        assert_eq!(
+            clock.system_time().duration_since(handle.start_system)?,
+            Duration::from_secs(60)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+//! Consent-gated tracking, anonymizing or dropping events until the user consents
+
+use crate::sdk::payload::{DEFAULT_PROFILE_ID_KEY, DEFAULT_TYPE_FIELD, DEVICE_ID_PROPERTY};
+use crate::sdk::{TrackType, Tracker};
+use crate::{TrackerError, TrackerResult};
+use serde_json::{Value, json};
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+/// Reason of [TrackerError::Filtered] for events dropped while consent is
+/// [ConsentState::Unknown]
+pub const CONSENT_UNKNOWN_REASON: &str = "consent unknown";
+/// Reason of [TrackerError::Filtered] for profile calls, e.g. `identify`, dropped while
+/// consent is [ConsentState::Denied]
+pub const CONSENT_DENIED_REASON: &str = "consent denied";
+
+/// Consent of the user to tracking, set via [Tracker::with_consent] and [Tracker::set_consent]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConsentState {
+    /// Send events as usual
+    #[default]
+    Granted,
+    /// Send `track` events anonymized, without profile id, device id and the properties
+    /// declared via [Tracker::with_pii_properties]. Profile calls like `identify` and
+    /// `increment` are dropped.
+    Denied,
+    /// Drop all events, e.g. until the consent banner was answered
+    Unknown,
+}
+
+impl Tracker {
+    /// Set the consent of the user. Defaults to [ConsentState::Granted].
+    pub fn with_consent(self, state: ConsentState) -> Self {
+        self.set_consent(state);
+        self
+    }
+
+    /// Change the consent of the user, e.g. once the consent banner was answered. Takes
+    /// effect for all requests sent afterwards, including events queued for a background
+    /// sender.
+    pub fn set_consent(&self, state: ConsentState) {
+        *self.consent.write().unwrap() = state;
+    }
+
+    /// Current consent of the user
+    pub fn consent(&self) -> ConsentState {
+        *self.consent.read().unwrap()
+    }
+
+    /// Declare property keys holding personal data, e.g. `email` or `ip`. These are stripped
+    /// from events sent while consent is [ConsentState::Denied], including global properties.
+    /// The keys are converted by [Tracker::with_key_normalization] like the properties.
+    pub fn with_pii_properties(mut self, keys: HashSet<String>) -> Self {
+        self.pii_properties = keys;
+        self
+    }
+
+    /// Anonymize or drop the payload according to the consent
+    pub(crate) fn apply_consent(&self, payload: Value) -> TrackerResult<Value> {
+        match self.consent() {
+            ConsentState::Granted => Ok(payload),
+            ConsentState::Unknown => Err(TrackerError::Filtered {
+                reason: CONSENT_UNKNOWN_REASON.to_string(),
+            }),
+            ConsentState::Denied => anonymize(payload, &self.normalized_pii_properties()),
+        }
+    }
+
+    /// PII property keys as sent, i.e. converted by the key normalization like the properties
+    fn normalized_pii_properties(&self) -> Cow<'_, HashSet<String>> {
+        match &self.key_normalization {
+            Some(normalization) => Cow::Owned(
+                self.pii_properties
+                    .iter()
+                    .map(|key| normalization.apply(key))
+                    .collect(),
+            ),
+            None => Cow::Borrowed(&self.pii_properties),
+        }
+    }
+}
+
+/// Strip the identifiers and personal properties of a `track` payload. Other payload types
+/// are about a profile and can't be anonymized.
+fn anonymize(mut payload: Value, pii_properties: &HashSet<String>) -> TrackerResult<Value> {
+    let denied = || TrackerError::Filtered {
+        reason: CONSENT_DENIED_REASON.to_string(),
+    };
+
+    if payload.get(DEFAULT_TYPE_FIELD) != Some(&json!(TrackType::Track)) {
+        return Err(denied());
+    }
+
+    let body = payload
+        .get_mut("payload")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(denied)?;
+
+    body.remove(DEFAULT_PROFILE_ID_KEY);
+
+    if let Some(properties) = body.get_mut("properties").and_then(Value::as_object_mut) {
+        properties.retain(|key, _| key != DEVICE_ID_PROPERTY && !pii_properties.contains(key));
+    }
+
+    Ok(payload)
+}
+
+#[cfg(all(test, not(feature = "disabled-at-compile-time")))]
+mod tests {
+    use super::*;
+    use crate::sdk::normalize::Normalization;
+    use crate::sdk::testing::MockTransport;
+    use crate::sdk::user::IdentifyUser;
+    use std::collections::HashMap;
+
+    fn tracker(transport: &MockTransport) -> Tracker {
+        transport
+            .tracker()
+            .with_global_properties(HashMap::from([("ip".to_string(), "10.0.0.1".to_string())]))
+            .with_pii_properties(HashSet::from(["email".to_string(), "ip".to_string()]))
+            .with_device_id("device_123".to_string())
+    }
+
+    async fn track(tracker: &Tracker) -> TrackerResult<reqwest::Response> {
+        let properties = HashMap::from([
+            ("email".to_string(), "user@example.com".to_string()),
+            ("plan".to_string(), "pro".to_string()),
+        ]);
+
+        tracker
+            .track(
+                "signup".to_string(),
+                Some("user_123".to_string()),
+                Some(properties),
+                None,
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn denied_consent_anonymizes_events() -> anyhow::Result<()> {
+        let transport = MockTransport::new();
+        let tracker = tracker(&transport).with_consent(ConsentState::Denied);
+
+        track(&tracker).await?;
+
+        let payload = transport.assert_tracked("signup");
+
+        assert!(payload["payload"].get(DEFAULT_PROFILE_ID_KEY).is_none());
+        assert_eq!(payload["payload"]["properties"], json!({"plan": "pro"}));
+
+        let result = tracker
+            .identify(IdentifyUser {
+                profile_id: "user_123".to_string(),
+                email: "user@example.com".to_string(),
+                first_name: "Jane".to_string(),
+                last_name: "Doe".to_string(),
+                properties: HashMap::new(),
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(TrackerError::Filtered { reason }) if reason == CONSENT_DENIED_REASON
+        ));
+        assert_eq!(transport.payloads().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unknown_consent_drops_events() -> anyhow::Result<()> {
+        let transport = MockTransport::new();
+        let tracker = tracker(&transport).with_consent(ConsentState::Unknown);
+
+        assert!(!tracker.would_send("signup", None, &HashMap::new()));
+        assert!(matches!(
+            track(&tracker).await,
+            Err(TrackerError::Filtered { reason }) if reason == CONSENT_UNKNOWN_REASON
+        ));
+        assert!(transport.payloads().is_empty());
+
+        tracker.set_consent(ConsentState::Granted);
+        track(&tracker).await?;
+
+        let payload = transport.assert_tracked("signup");
+
+        assert_eq!(payload["payload"]["profileId"], "user_123");
+        assert_eq!(
+            payload["payload"]["properties"]["email"],
+            "user@example.com"
+        );
+        assert_eq!(payload["payload"]["properties"]["ip"], "10.0.0.1");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replayed_events_follow_the_current_consent() -> anyhow::Result<()> {
+        let transport = MockTransport::new().with_response(503, "");
+        let tracker = tracker(&transport).with_replay_on_failure(10);
+
+        track(&tracker).await?;
+
+        assert_eq!(tracker.buffered_events(), 1);
+
+        tracker.set_consent(ConsentState::Denied);
+        track(&tracker).await?;
+
+        let payloads = transport.payloads();
+
+        assert_eq!(payloads.len(), 3);
+        assert_eq!(payloads[0]["payload"]["profileId"], "user_123");
+
+        for payload in &payloads[1..] {
+            assert!(payload["payload"].get(DEFAULT_PROFILE_ID_KEY).is_none());
+            assert_eq!(payload["payload"]["properties"], json!({"plan": "pro"}));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn strips_pii_properties_with_normalized_keys() -> anyhow::Result<()> {
+        let transport = MockTransport::new();
+        let tracker = transport
+            .tracker()
+            .with_key_normalization(Normalization::SnakeCase)
+            .with_pii_properties(HashSet::from(["userEmail".to_string()]))
+            .with_consent(ConsentState::Denied);
+        let properties = HashMap::from([
+            ("userEmail".to_string(), "user@example.com".to_string()),
+            ("plan".to_string(), "pro".to_string()),
+        ]);
+
+        tracker
+            .track("signup".to_string(), None, Some(properties), None)
+            .await?;
+
+        let payload = transport.assert_tracked("signup");
+
+        assert_eq!(payload["payload"]["properties"], json!({"plan": "pro"}));
+
+        Ok(())
+    }
+}
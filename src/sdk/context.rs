@@ -0,0 +1,218 @@
+//! Runtime and device context attached to every event as global properties
+
+use std::collections::HashMap;
+
+/// Property name of the host name
+pub const HOSTNAME_PROPERTY: &str = "hostname";
+/// Property name of the process id
+pub const PID_PROPERTY: &str = "pid";
+/// Property name of the service version
+pub const SERVICE_VERSION_PROPERTY: &str = "service_version";
+/// Property name of the operating system
+pub const OS_PROPERTY: &str = "os";
+/// Property name of the operating system version
+pub const OS_VERSION_PROPERTY: &str = "os_version";
+/// Property name of the app version
+pub const APP_VERSION_PROPERTY: &str = "app_version";
+/// Property name of the device model
+pub const DEVICE_MODEL_PROPERTY: &str = "device_model";
+/// Property name of the screen width in pixels
+pub const SCREEN_WIDTH_PROPERTY: &str = "screen_width";
+/// Property name of the screen height in pixels
+pub const SCREEN_HEIGHT_PROPERTY: &str = "screen_height";
+
+/// Operational context of the running process, added via [crate::sdk::Tracker::with_runtime_context].
+/// Host name and process id are enabled by default, the service version has to be passed in,
+/// e.g. `env!("CARGO_PKG_VERSION")` of the host crate.
+#[derive(Debug, Clone)]
+pub struct RuntimeContext {
+    hostname: bool,
+    pid: bool,
+    service_version: Option<String>,
+}
+
+impl Default for RuntimeContext {
+    fn default() -> Self {
+        Self {
+            hostname: true,
+            pid: true,
+            service_version: None,
+        }
+    }
+}
+
+impl RuntimeContext {
+    /// Create context with host name and process id enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether the host name is added
+    pub fn with_hostname(mut self, enabled: bool) -> Self {
+        self.hostname = enabled;
+        self
+    }
+
+    /// Set whether the process id is added
+    pub fn with_pid(mut self, enabled: bool) -> Self {
+        self.pid = enabled;
+        self
+    }
+
+    /// Set the service version added to every event
+    pub fn with_service_version(mut self, version: String) -> Self {
+        self.service_version = Some(version);
+        self
+    }
+}
+
+impl From<RuntimeContext> for HashMap<String, String> {
+    fn from(context: RuntimeContext) -> Self {
+        let mut properties = HashMap::new();
+
+        if context.hostname {
+            if let Some(hostname) = hostname() {
+                properties.insert(HOSTNAME_PROPERTY.to_string(), hostname);
+            }
+        }
+
+        if context.pid {
+            properties.insert(PID_PROPERTY.to_string(), std::process::id().to_string());
+        }
+
+        if let Some(version) = context.service_version {
+            properties.insert(SERVICE_VERSION_PROPERTY.to_string(), version);
+        }
+
+        properties
+    }
+}
+
+/// Context of the device running a mobile or desktop app, added via
+/// [crate::sdk::Tracker::with_device_context]. Fields which aren't set are omitted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceContext {
+    pub os: Option<String>,
+    pub os_version: Option<String>,
+    pub app_version: Option<String>,
+    pub device_model: Option<String>,
+    /// Screen width and height in pixels
+    pub screen_size: Option<(u32, u32)>,
+}
+
+impl DeviceContext {
+    /// Create context without any field set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the operating system, e.g. `iOS`
+    pub fn with_os(mut self, os: String) -> Self {
+        self.os = Some(os);
+        self
+    }
+
+    /// Set the operating system version, e.g. `17.4`
+    pub fn with_os_version(mut self, version: String) -> Self {
+        self.os_version = Some(version);
+        self
+    }
+
+    /// Set the app version, e.g. `env!("CARGO_PKG_VERSION")` of the app crate
+    pub fn with_app_version(mut self, version: String) -> Self {
+        self.app_version = Some(version);
+        self
+    }
+
+    /// Set the device model, e.g. `iPhone15,2`
+    pub fn with_device_model(mut self, model: String) -> Self {
+        self.device_model = Some(model);
+        self
+    }
+
+    /// Set the screen size in pixels
+    pub fn with_screen_size(mut self, width: u32, height: u32) -> Self {
+        self.screen_size = Some((width, height));
+        self
+    }
+}
+
+impl From<DeviceContext> for HashMap<String, String> {
+    fn from(context: DeviceContext) -> Self {
+        let fields = [
+            (OS_PROPERTY, context.os),
+            (OS_VERSION_PROPERTY, context.os_version),
+            (APP_VERSION_PROPERTY, context.app_version),
+            (DEVICE_MODEL_PROPERTY, context.device_model),
+            (
+                SCREEN_WIDTH_PROPERTY,
+                context.screen_size.map(|(width, _)| width.to_string()),
+            ),
+            (
+                SCREEN_HEIGHT_PROPERTY,
+                context.screen_size.map(|(_, height)| height.to_string()),
+            ),
+        ];
+
+        fields
+            .into_iter()
+            .filter_map(|(key, value)| Some((key.to_string(), value?)))
+            .collect()
+    }
+}
+
+/// Read the host name from the environment, falling back to the kernel on Linux
+fn hostname() -> Option<String> {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/proc/sys/kernel/hostname").ok())
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|hostname| hostname.trim().to_string())
+        .filter(|hostname| !hostname.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_toggle_context_fields() {
+        let properties: HashMap<String, String> = RuntimeContext::new()
+            .with_hostname(false)
+            .with_service_version("1.2.3".to_string())
+            .into();
+
+        assert!(!properties.contains_key(HOSTNAME_PROPERTY));
+        assert_eq!(
+            properties.get(PID_PROPERTY),
+            Some(&std::process::id().to_string())
+        );
+        assert_eq!(
+            properties.get(SERVICE_VERSION_PROPERTY).map(String::as_str),
+            Some("1.2.3")
+        );
+
+        let properties: HashMap<String, String> = RuntimeContext::new().with_pid(false).into();
+
+        assert!(!properties.contains_key(PID_PROPERTY));
+        assert!(!properties.contains_key(SERVICE_VERSION_PROPERTY));
+    }
+
+    #[test]
+    fn omits_unset_device_fields() {
+        let properties: HashMap<String, String> = DeviceContext::new()
+            .with_os("iOS".to_string())
+            .with_screen_size(1179, 2556)
+            .into();
+
+        assert_eq!(
+            properties,
+            HashMap::from([
+                (OS_PROPERTY.to_string(), "iOS".to_string()),
+                (SCREEN_WIDTH_PROPERTY.to_string(), "1179".to_string()),
+                (SCREEN_HEIGHT_PROPERTY.to_string(), "2556".to_string()),
+            ])
+        );
+        assert!(HashMap::<String, String>::from(DeviceContext::new()).is_empty());
+    }
+}
@@ -0,0 +1,256 @@
+//! Keys of events sent via [crate::sdk::Tracker::track_once], so each is sent at most once
+
+use crate::sdk::Tracker;
+use crate::{TrackerError, TrackerResult};
+use reqwest::Response;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
+
+/// Reason of [TrackerError::Filtered] for events whose dedup key was seen already
+pub const DUPLICATE_FILTER_REASON: &str = "dedup key tracked already";
+
+/// Default number of keys remembered, set via [crate::sdk::Tracker::with_dedup_capacity]
+pub const DEFAULT_DEDUP_CAPACITY: usize = 10_000;
+
+/// Storage of sent dedup keys, set via [crate::sdk::Tracker::with_dedup_store].
+/// Implement this to remember keys across restarts, e.g. in a file or database.
+pub trait DedupStore: Send + Sync {
+    /// Previously saved keys, oldest first
+    fn load(&self) -> Vec<String>;
+    /// Save the key of an event that was sent
+    fn save(&self, key: &str);
+}
+
+/// Bounded set of seen keys, forgetting the oldest key once full
+pub(crate) struct SeenKeys {
+    capacity: usize,
+    state: Mutex<State>,
+    store: Option<Box<dyn DedupStore>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    keys: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl State {
+    fn insert(&mut self, key: String, capacity: usize) {
+        if self.keys.insert(key.clone()) {
+            self.order.push_back(key);
+        }
+
+        while self.order.len() > capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.keys.remove(&oldest);
+            }
+        }
+    }
+}
+
+impl Debug for SeenKeys {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SeenKeys")
+            .field("capacity", &self.capacity)
+            .field("len", &self.state.lock().unwrap().order.len())
+            .field("store", &self.store.as_ref().map(|_| "dyn DedupStore"))
+            .finish()
+    }
+}
+
+impl SeenKeys {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(State::default()),
+            store: None,
+        }
+    }
+
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+    }
+
+    /// Use the store, adding the keys saved in it
+    pub(crate) fn set_store(&mut self, store: Box<dyn DedupStore>) {
+        let state = self.state.get_mut().unwrap();
+
+        for key in store.load() {
+            state.insert(key, self.capacity);
+        }
+
+        self.store = Some(store);
+    }
+
+    /// Reserve the key, returning `None` if it was seen already. The key is released again
+    /// when the reservation is dropped without being confirmed.
+    pub(crate) fn reserve(&self, key: &str) -> Option<Reservation<'_>> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.keys.contains(key) {
+            return None;
+        }
+
+        state.insert(key.to_string(), self.capacity);
+
+        Some(Reservation {
+            seen: self,
+            key: key.to_string(),
+            confirmed: false,
+        })
+    }
+
+    fn release(&self, key: &str) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.keys.remove(key) {
+            state.order.retain(|seen| seen != key);
+        }
+    }
+}
+
+/// Key reserved while its event is sent
+pub(crate) struct Reservation<'a> {
+    seen: &'a SeenKeys,
+    key: String,
+    confirmed: bool,
+}
+
+impl Reservation<'_> {
+    /// Keep the key as sent and save it to the store
+    pub(crate) fn confirm(mut self) {
+        self.confirmed = true;
+
+        if let Some(store) = &self.seen.store {
+            store.save(&self.key);
+        }
+    }
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if !self.confirmed {
+            self.seen.release(&self.key);
+        }
+    }
+}
+
+impl Tracker {
+    /// Remember at most `capacity` keys of [Tracker::track_once], forgetting the oldest ones
+    /// first. Defaults to [DEFAULT_DEDUP_CAPACITY].
+    pub fn with_dedup_capacity(mut self, capacity: usize) -> Self {
+        self.seen_keys.set_capacity(capacity);
+        self
+    }
+
+    /// Load and save the keys of [Tracker::track_once] via the store, so events are not sent
+    /// again after a restart
+    pub fn with_dedup_store(mut self, store: impl DedupStore + 'static) -> Self {
+        self.seen_keys.set_store(Box::new(store));
+        self
+    }
+
+    /// Track event like [Tracker::track] at most once per `dedup_key`, e.g. an order id.
+    /// Returns [TrackerError::Filtered] if an event with the key was sent already or is being
+    /// sent. If sending fails, OpenPanel responds with a non-2xx status or the call is
+    /// cancelled, the key is released, so the event can be tracked again.
+    pub async fn track_once(
+        &self,
+        dedup_key: String,
+        event: String,
+        profile_id: Option<String>,
+        properties: Option<HashMap<String, String>>,
+    ) -> TrackerResult<Response> {
+        let Some(reservation) = self.seen_keys.reserve(&dedup_key) else {
+            tracing::debug!("Event with dedup key {} was tracked already", dedup_key);
+            return Err(TrackerError::Filtered {
+                reason: DUPLICATE_FILTER_REASON.to_string(),
+            });
+        };
+
+        let response = self.track_unfiltered(event, profile_id, properties).await?;
+
+        if response.status().is_success() {
+            reservation.confirm();
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    use crate::sdk::testing::MockTransport;
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    use std::sync::Arc;
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[derive(Clone, Default)]
+    struct SharedStore(Arc<Mutex<Vec<String>>>);
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    impl DedupStore for SharedStore {
+        fn load(&self) -> Vec<String> {
+            self.0.lock().unwrap().clone()
+        }
+
+        fn save(&self, key: &str) {
+            self.0.lock().unwrap().push(key.to_string());
+        }
+    }
+
+    #[test]
+    fn forgets_oldest_keys_when_full() {
+        let seen = SeenKeys::new(2);
+
+        for key in ["a", "b", "c"] {
+            seen.reserve(key).unwrap().confirm();
+        }
+
+        assert!(seen.reserve("b").is_none());
+        assert!(seen.reserve("a").is_some());
+    }
+
+    #[test]
+    fn unconfirmed_keys_are_released() {
+        let seen = SeenKeys::new(2);
+        let reservation = seen.reserve("a");
+
+        assert!(seen.reserve("a").is_none());
+
+        drop(reservation);
+
+        assert!(seen.reserve("a").is_some());
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[tokio::test]
+    async fn tracks_each_key_once() -> anyhow::Result<()> {
+        let store = SharedStore::default();
+        let transport = MockTransport::new().with_response(500, "");
+        let tracker = transport.tracker().with_dedup_store(store.clone());
+        let track =
+            |key: &str| tracker.track_once(key.to_string(), "order".to_string(), None, None);
+
+        assert_eq!(track("order_1").await?.status(), 500);
+        assert_eq!(track("order_1").await?.status(), 200);
+        assert!(matches!(
+            track("order_1").await,
+            Err(TrackerError::Filtered { .. })
+        ));
+        assert_eq!(transport.events().len(), 2);
+        assert_eq!(store.load(), vec!["order_1"]);
+
+        let restarted = MockTransport::new().tracker().with_dedup_store(store);
+        let result = restarted
+            .track_once("order_1".to_string(), "order".to_string(), None, None)
+            .await;
+
+        assert!(matches!(result, Err(TrackerError::Filtered { .. })));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+//! Accounting of the bytes sent per tracked event
+
+use reqwest::Response;
+
+/// Size in bytes of the request bodies sent for an event, attached to the responses returned
+/// by [crate::sdk::Tracker::track] and the other sending methods. Counts the body as
+/// serialized by the [crate::sdk::serializer::PayloadSerializer], so it reflects custom wire
+/// formats, and includes retried attempts which received a response. Headers aren't counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BytesSent(pub usize);
+
+/// Bytes sent for the event of the response, `None` if the response wasn't returned by the
+/// tracker
+pub fn bytes_sent(response: &Response) -> Option<usize> {
+    response
+        .extensions()
+        .get::<BytesSent>()
+        .map(|bytes_sent| bytes_sent.0)
+}
+
+/// Add the bytes to the response's [BytesSent]
+pub(crate) fn add_bytes_sent(response: &mut Response, bytes: usize) {
+    let total = bytes_sent(response).unwrap_or_default() + bytes;
+
+    response.extensions_mut().insert(BytesSent(total));
+}
+
+#[cfg(all(test, not(feature = "disabled-at-compile-time")))]
+mod tests {
+    use super::*;
+    use crate::sdk::retry::RetryPolicy;
+    use crate::sdk::testing::MockTransport;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn responses_carry_bytes_sent() -> anyhow::Result<()> {
+        let transport = MockTransport::new();
+        // the captured response is rebuilt, which has to keep the extension
+        let tracker = transport.tracker().with_debug_capture(true);
+        let properties = HashMap::from([("plan".to_string(), "pro".repeat(100))]);
+
+        let small = tracker.track("test".to_string(), None, None, None).await?;
+        let large = tracker
+            .track("test".to_string(), None, Some(properties), None)
+            .await?;
+        let payloads = transport.payloads();
+
+        assert_eq!(
+            bytes_sent(&small),
+            Some(serde_json::to_vec(&payloads[0])?.len())
+        );
+        assert_eq!(
+            bytes_sent(&large),
+            Some(serde_json::to_vec(&payloads[1])?.len())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn counts_retried_attempts() -> anyhow::Result<()> {
+        let transport = MockTransport::new()
+            .with_response(503, "")
+            .with_response(200, "");
+        let tracker = transport.tracker().with_retry_policy(
+            RetryPolicy::default()
+                .with_max_retries(1)
+                .with_base_delay(Duration::from_millis(1)),
+        );
+
+        let response = tracker.track("test".to_string(), None, None, None).await?;
+        let body = serde_json::to_vec(&transport.payloads()[0])?.len();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(bytes_sent(&response), Some(2 * body));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+//! Standardized `error` events for caught errors
+
+use crate::TrackerResult;
+use crate::sdk::Tracker;
+use reqwest::Response;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Name of events tracked with [Tracker::track_error]
+pub const ERROR_EVENT: &str = "error";
+/// Property name of the error message
+pub const MESSAGE_PROPERTY: &str = "message";
+/// Property name of the error type, taken from the `Debug` output, e.g. `ParseIntError`
+pub const ERROR_TYPE_PROPERTY: &str = "error_type";
+/// Property name of the messages of the error's sources, outermost first, joined by
+/// [SOURCE_SEPARATOR]. Only set if the error has a source.
+pub const SOURCE_CHAIN_PROPERTY: &str = "source_chain";
+/// Separator of the messages in [SOURCE_CHAIN_PROPERTY]
+pub const SOURCE_SEPARATOR: &str = " <- ";
+
+/// Properties describing the error
+pub fn error_properties(error: &dyn Error) -> HashMap<String, String> {
+    let mut properties = HashMap::from([
+        (MESSAGE_PROPERTY.to_string(), error.to_string()),
+        (ERROR_TYPE_PROPERTY.to_string(), error_type(error)),
+    ]);
+    let mut sources = Vec::new();
+    let mut source = error.source();
+
+    while let Some(error) = source {
+        sources.push(error.to_string());
+        source = error.source();
+    }
+
+    if !sources.is_empty() {
+        properties.insert(
+            SOURCE_CHAIN_PROPERTY.to_string(),
+            sources.join(SOURCE_SEPARATOR),
+        );
+    }
+
+    properties
+}
+
+/// Leading identifier of the `Debug` output, the type or variant name for derived impls
+fn error_type(error: &dyn Error) -> String {
+    let debug = format!("{:?}", error);
+    let name = debug
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default();
+
+    if name.is_empty() {
+        "Error".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+impl Tracker {
+    /// Track a caught error as [ERROR_EVENT] event with [MESSAGE_PROPERTY],
+    /// [ERROR_TYPE_PROPERTY] and [SOURCE_CHAIN_PROPERTY], in addition to global properties
+    /// and the context. The error properties take precedence over context with the same names.
+    pub async fn track_error(
+        &self,
+        error: &dyn Error,
+        profile_id: Option<String>,
+        context: Option<HashMap<String, String>>,
+    ) -> TrackerResult<Response> {
+        let mut properties = context.unwrap_or_default();
+
+        properties.extend(error_properties(error));
+
+        self.track_unfiltered(ERROR_EVENT.to_string(), profile_id, Some(properties))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    use crate::sdk::testing::MockTransport;
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    use serde_json::json;
+
+    #[derive(Debug)]
+    struct LoadError(std::num::ParseIntError);
+
+    impl std::fmt::Display for LoadError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "invalid config")
+        }
+    }
+
+    impl Error for LoadError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn can_describe_errors() {
+        let error = "x".parse::<u32>().unwrap_err();
+        let properties = error_properties(&error);
+
+        assert_eq!(properties[ERROR_TYPE_PROPERTY], "ParseIntError");
+        assert_eq!(properties[MESSAGE_PROPERTY], error.to_string());
+        assert!(!properties.contains_key(SOURCE_CHAIN_PROPERTY));
+
+        let properties = error_properties(&LoadError(error));
+
+        assert_eq!(properties[ERROR_TYPE_PROPERTY], "LoadError");
+        assert_eq!(
+            properties[SOURCE_CHAIN_PROPERTY],
+            "invalid digit found in string"
+        );
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[tokio::test]
+    async fn can_track_errors_with_context() -> anyhow::Result<()> {
+        let transport = MockTransport::new();
+        let error = LoadError("x".parse::<u32>().unwrap_err());
+        let context = HashMap::from([
+            ("file".to_string(), "app.toml".to_string()),
+            (MESSAGE_PROPERTY.to_string(), "overridden".to_string()),
+        ]);
+
+        transport
+            .tracker()
+            .track_error(&error, Some("rust_123".to_string()), Some(context))
+            .await?;
+
+        transport.assert_received(&json!({"payload": {
+            "name": ERROR_EVENT,
+            "profileId": "rust_123",
+            "properties": {
+                "message": "invalid config",
+                "error_type": "LoadError",
+                "source_chain": "invalid digit found in string",
+                "file": "app.toml",
+            },
+        }}));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+//! Capture of the last request and response for debugging rejected events
+
+use crate::TrackerResult;
+use crate::sdk::Tracker;
+use reqwest::Response;
+
+/// Last payload sent and the response received, recorded if debug capture is enabled via
+/// [Tracker::with_debug_capture]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exchange {
+    /// Payload as sent to OpenPanel
+    pub payload: serde_json::Value,
+    /// HTTP status returned by OpenPanel, if a response was received
+    pub status: Option<u16>,
+    /// Response body, if a response was received
+    pub body: Option<String>,
+    /// Error message, if no response was received
+    pub error: Option<String>,
+}
+
+impl Tracker {
+    /// Record the last payload and response, see [Tracker::last_exchange]. Holds the last
+    /// payload and response body in memory, so only enable this while debugging.
+    pub fn with_debug_capture(mut self, enabled: bool) -> Self {
+        self.debug_capture = enabled;
+        self
+    }
+
+    /// Last payload sent and the response received, if debug capture is enabled and a
+    /// request was sent
+    pub fn last_exchange(&self) -> Option<Exchange> {
+        self.last_exchange.read().unwrap().clone()
+    }
+
+    /// Record the exchange if debug capture is enabled. The response body is read and the
+    /// returned response is rebuilt from it, so it no longer carries the request URL.
+    pub(crate) async fn capture_exchange(
+        &self,
+        payload: &serde_json::Value,
+        result: TrackerResult<Response>,
+    ) -> TrackerResult<Response> {
+        if !self.debug_capture {
+            return result;
+        }
+
+        let mut exchange = Exchange {
+            payload: payload.clone(),
+            status: None,
+            body: None,
+            error: None,
+        };
+
+        let result = match result {
+            Ok(response) => {
+                let status = response.status();
+                let version = response.version();
+                let headers = response.headers().clone();
+                let extensions = response.extensions().clone();
+                let body = response.bytes().await;
+
+                exchange.status = Some(status.as_u16());
+
+                match body {
+                    Ok(body) => {
+                        exchange.body = Some(String::from_utf8_lossy(&body).into_owned());
+
+                        let mut rebuilt = http::Response::new(body);
+
+                        *rebuilt.status_mut() = status;
+                        *rebuilt.version_mut() = version;
+                        *rebuilt.headers_mut() = headers;
+                        *rebuilt.extensions_mut() = extensions;
+
+                        Ok(Response::from(rebuilt))
+                    }
+                    Err(e) => {
+                        exchange.error = Some(e.to_string());
+                        Err(e.into())
+                    }
+                }
+            }
+            Err(e) => {
+                exchange.error = Some(e.to_string());
+                Err(e)
+            }
+        };
+
+        *self.last_exchange.write().unwrap() = Some(exchange);
+
+        result
+    }
+}
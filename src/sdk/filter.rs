@@ -0,0 +1,290 @@
+//! Named event filters registered on the tracker, combined by a [FilterPolicy]
+
+use crate::TrackerResult;
+use crate::sdk::Tracker;
+use crate::sdk::property::PropertyValue;
+use reqwest::Response;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+
+/// Reason of [crate::TrackerError::Filtered] if a per-call filter returning `bool` drops the event
+pub const DEFAULT_FILTER_REASON: &str = "dropped by filter";
+/// Reason of [crate::TrackerError::Filtered] if the profile isn't part of the rollout
+pub const ROLLOUT_FILTER_REASON: &str = "profile not in rollout";
+
+/// Per-call filter passed to [Tracker::track_with_reason_filter]. Returns the reason if the
+/// event should be dropped.
+pub type ReasonFilter<'a> = dyn Fn(&HashMap<String, String>) -> Option<String> + Sync + 'a;
+
+/// Filter registered via [Tracker::add_filter], receiving the event name and properties.
+/// Returns `true` if the event should be dropped.
+pub type EventFilter = dyn Fn(&str, &HashMap<String, String>) -> bool + Send + Sync;
+
+/// Combination of the filters registered via [Tracker::add_filter], set via
+/// [Tracker::with_filter_policy]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FilterPolicy {
+    /// Drop the event if any filter matches, i.e. every filter has to pass
+    #[default]
+    Any,
+    /// Drop the event only if all filters match
+    All,
+}
+
+/// Filters in registration order
+#[derive(Default)]
+pub(crate) struct FilterChain {
+    filters: Vec<(String, Box<EventFilter>)>,
+    policy: FilterPolicy,
+}
+
+impl Debug for FilterChain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilterChain")
+            .field(
+                "filters",
+                &self
+                    .filters
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+/// Property value of an event passed to the filters. Filters receive string properties, so
+/// other values are passed as JSON, e.g. `42` or `["a","b"]`.
+pub(crate) trait FilterValue: Sized {
+    /// Properties as passed to the filters
+    fn filter_view(properties: &HashMap<String, Self>) -> Cow<'_, HashMap<String, String>>;
+}
+
+impl FilterValue for String {
+    fn filter_view(properties: &HashMap<String, Self>) -> Cow<'_, HashMap<String, String>> {
+        Cow::Borrowed(properties)
+    }
+}
+
+impl FilterValue for PropertyValue {
+    fn filter_view(properties: &HashMap<String, Self>) -> Cow<'_, HashMap<String, String>> {
+        Cow::Owned(
+            properties
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        PropertyValue::String(value) => value.clone(),
+                        value => serde_json::to_string(value).unwrap_or_default(),
+                    };
+
+                    (key.clone(), value)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl FilterValue for serde_json::Value {
+    fn filter_view(properties: &HashMap<String, Self>) -> Cow<'_, HashMap<String, String>> {
+        Cow::Owned(
+            properties
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        serde_json::Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    };
+
+                    (key.clone(), value)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl FilterChain {
+    /// Whether no filters are registered
+    pub(crate) fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// Names of the filters dropping the event, joined by `, `. `None` if the event passes.
+    pub(crate) fn dropped_by(
+        &self,
+        event: &str,
+        properties: &HashMap<String, String>,
+    ) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut matching = self
+            .filters
+            .iter()
+            .filter(|(_, filter)| filter(event, properties))
+            .map(|(name, _)| name.as_str());
+
+        match self.policy {
+            FilterPolicy::Any => matching.next().map(str::to_string),
+            FilterPolicy::All => {
+                let matching = matching.collect::<Vec<_>>();
+
+                (matching.len() == self.filters.len()).then(|| matching.join(", "))
+            }
+        }
+    }
+}
+
+impl Tracker {
+    /// Register a named filter evaluated by [Tracker::track], [Tracker::track_with_values] and
+    /// [Tracker::track_revenue] after the per-call filter. Non-string property values are
+    /// passed as JSON.
+    /// Events dropped by the filters return [crate::TrackerError::Filtered] with the reason
+    /// `filter <name>`. Filters are combined via [Tracker::with_filter_policy].
+    pub fn add_filter(
+        mut self,
+        name: String,
+        filter: impl Fn(&str, &HashMap<String, String>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.filters.filters.push((name, Box::new(filter)));
+        self
+    }
+
+    /// Set how the filters registered via [Tracker::add_filter] are combined. Defaults to
+    /// [FilterPolicy::Any].
+    pub fn with_filter_policy(mut self, policy: FilterPolicy) -> Self {
+        self.filters.policy = policy;
+        self
+    }
+
+    /// Track event like [Tracker::track] with a filter returning the reason if the event
+    /// should be dropped. The reason is returned as [crate::TrackerError::Filtered].
+    pub async fn track_with_reason_filter(
+        &self,
+        event: String,
+        profile_id: Option<String>,
+        properties: Option<HashMap<String, String>>,
+        filter: &ReasonFilter<'_>,
+    ) -> TrackerResult<Response> {
+        let profile_id = self.resolve_profile_id(profile_id);
+        let ticket = self.order_ticket(profile_id.as_deref());
+
+        self.track_in_order(event, profile_id, properties, Some(filter), None, ticket)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TrackerError;
+    use crate::sdk::testing::MockTransport;
+
+    fn property(properties: &HashMap<String, String>, key: &str) -> bool {
+        properties.get(key).is_some_and(|value| value == "true")
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    fn tracker(transport: &MockTransport, policy: FilterPolicy) -> Tracker {
+        transport
+            .tracker()
+            .add_filter("debug".to_string(), |_, properties| {
+                property(properties, "debug")
+            })
+            .add_filter("low_priority".to_string(), |event, _| event != "conversion")
+            .with_filter_policy(policy)
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    async fn track(tracker: &Tracker, event: &str, debug: bool) -> Result<(), String> {
+        let properties = HashMap::from([("debug".to_string(), debug.to_string())]);
+
+        match tracker
+            .track(event.to_string(), None, Some(properties), None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(TrackerError::Filtered { reason }) => Err(reason),
+            Err(e) => panic!("unexpected error {}", e),
+        }
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[tokio::test]
+    async fn any_matching_filter_drops_events() {
+        let transport = MockTransport::new();
+        let tracker = tracker(&transport, FilterPolicy::Any);
+
+        assert_eq!(
+            track(&tracker, "click", false).await,
+            Err("filter low_priority".to_string())
+        );
+        assert_eq!(
+            track(&tracker, "conversion", true).await,
+            Err("filter debug".to_string())
+        );
+        assert_eq!(track(&tracker, "conversion", false).await, Ok(()));
+        assert_eq!(transport.events(), vec!["conversion"]);
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[tokio::test]
+    async fn all_filters_have_to_match_to_drop_events() {
+        let transport = MockTransport::new();
+        let tracker = tracker(&transport, FilterPolicy::All);
+
+        assert_eq!(
+            track(&tracker, "click", true).await,
+            Err("filter debug, low_priority".to_string())
+        );
+        assert_eq!(track(&tracker, "conversion", true).await, Ok(()));
+        assert_eq!(track(&tracker, "click", false).await, Ok(()));
+        assert_eq!(transport.events(), vec!["conversion", "click"]);
+    }
+
+    #[tokio::test]
+    async fn filters_return_the_reason() {
+        let transport = MockTransport::new();
+        let tracker = transport.tracker();
+        let filter = |properties: &HashMap<String, String>| {
+            property(properties, "debug").then(|| "debug build".to_string())
+        };
+        let properties = HashMap::from([("debug".to_string(), "true".to_string())]);
+
+        let result = tracker
+            .track_with_reason_filter("test".to_string(), None, Some(properties.clone()), &filter)
+            .await;
+
+        assert!(
+            matches!(result, Err(TrackerError::Filtered { reason }) if reason == "debug build")
+        );
+
+        let bool_filter = |properties: HashMap<String, String>| property(&properties, "debug");
+        let result = tracker
+            .track(
+                "test".to_string(),
+                None,
+                Some(properties),
+                Some(&bool_filter),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(TrackerError::Filtered { reason }) if reason == DEFAULT_FILTER_REASON
+        ));
+
+        let result = tracker
+            .with_rollout(0)
+            .track("test".to_string(), Some("rust_123".to_string()), None, None)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(TrackerError::Filtered { reason }) if reason == ROLLOUT_FILTER_REASON
+        ));
+        assert!(transport.events().is_empty());
+    }
+}
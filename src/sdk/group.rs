@@ -0,0 +1,14 @@
+//! Tracking group used for identify group calls
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Group object used for identify group calls, e.g. a company with its name and plan.
+/// Traits like the employee count go into `properties`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentifyGroup {
+    pub group_id: String,
+    pub name: String,
+    pub properties: HashMap<String, String>,
+}
@@ -0,0 +1,108 @@
+//! Periodic heartbeat events for presence tracking
+
+use crate::sdk::Tracker;
+use crate::{TrackerError, TrackerResult};
+use reqwest::Response;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Upper bound of the heartbeat delay while OpenPanel responds with 429, as multiple of the
+/// interval
+pub const MAX_BACKOFF_FACTOR: u32 = 8;
+
+/// Running heartbeat started by [Tracker::start_heartbeat]. Dropping the handle stops the
+/// heartbeat as well.
+#[derive(Debug)]
+pub struct HeartbeatHandle {
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl HeartbeatHandle {
+    /// Stop the heartbeat, waiting for a heartbeat being sent right now
+    pub async fn stop(self) {
+        let _ = self.stop.send(());
+        let _ = self.task.await;
+    }
+}
+
+impl Tracker {
+    /// Track `event` every `interval` in a background task, e.g. for active users dashboards.
+    /// Events are tracked like [Tracker::track] without a profile id, so nothing is sent if the
+    /// tracker is disabled. While OpenPanel responds with 429, the delay is doubled up to
+    /// [MAX_BACKOFF_FACTOR] times the interval.
+    pub fn start_heartbeat(self: &Arc<Self>, interval: Duration, event: String) -> HeartbeatHandle {
+        let tracker = Arc::clone(self);
+        let (stop, mut stopped) = oneshot::channel();
+
+        let task = self.spawn(async move {
+            let mut delay = interval;
+
+            loop {
+                tokio::select! {
+                    _ = &mut stopped => break,
+                    _ = tokio::time::sleep(delay) => {}
+                }
+
+                let result = tracker.track_unfiltered(event.clone(), None, None).await;
+
+                if let Err(e) = &result {
+                    if !e.is_intentional_skip() {
+                        tracing::debug!("Failed to send heartbeat: {}", e);
+                    }
+                }
+
+                delay = next_delay(interval, delay, &result);
+            }
+        });
+
+        HeartbeatHandle { stop, task }
+    }
+}
+
+/// Delay until the next heartbeat, backing off while rate limited
+fn next_delay(interval: Duration, delay: Duration, result: &TrackerResult<Response>) -> Duration {
+    match result {
+        Err(TrackerError::TooManyRequests) => delay
+            .saturating_mul(2)
+            .min(interval.saturating_mul(MAX_BACKOFF_FACTOR)),
+        _ => interval,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::builder::TrackerBuilder;
+
+    #[test]
+    fn backs_off_while_rate_limited() {
+        let interval = Duration::from_secs(1);
+        let mut delay = interval;
+        let mut delays = Vec::new();
+
+        for _ in 0..5 {
+            delay = next_delay(interval, delay, &Err(TrackerError::TooManyRequests));
+            delays.push(delay.as_secs());
+        }
+
+        assert_eq!(delays, vec![2, 4, 8, 8, 8]);
+        assert_eq!(
+            next_delay(interval, delay, &Err(TrackerError::Disabled)),
+            interval
+        );
+    }
+
+    #[tokio::test]
+    async fn can_stop_heartbeat() -> anyhow::Result<()> {
+        let tracker = Arc::new(TrackerBuilder::default().build()?);
+        let heartbeat = tracker.start_heartbeat(Duration::from_millis(1), "heartbeat".to_string());
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        tokio::time::timeout(Duration::from_secs(1), heartbeat.stop()).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+//! Cache of the last identify payload per profile, skipping identify calls which change nothing
+
+use crate::sdk::serializer::SortedKeys;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+/// Number of profiles remembered by the cache enabled via
+/// [crate::sdk::Tracker::with_identify_dedup]. The oldest profile is forgotten first.
+pub const IDENTIFY_CACHE_CAPACITY: usize = 1_000;
+
+/// Hashes of the last identify payload sent per profile id
+#[derive(Debug)]
+pub(crate) struct IdentifyCache {
+    capacity: usize,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    hashes: HashMap<String, u64>,
+    order: VecDeque<String>,
+}
+
+impl IdentifyCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Whether the payload was the last one sent for the profile
+    pub(crate) fn is_unchanged(&self, profile_id: &str, hash: u64) -> bool {
+        self.state.lock().unwrap().hashes.get(profile_id) == Some(&hash)
+    }
+
+    /// Remember the payload as the last one sent for the profile
+    pub(crate) fn insert(&self, profile_id: String, hash: u64) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.hashes.insert(profile_id.clone(), hash).is_some() {
+            state.order.retain(|id| *id != profile_id);
+        }
+
+        state.order.push_back(profile_id);
+
+        while state.order.len() > self.capacity {
+            if let Some(oldest) = state.order.pop_front() {
+                state.hashes.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Hash of the payload, independent of the order of its properties
+pub(crate) fn hash_payload(payload: &serde_json::Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    serde_json::to_string(&SortedKeys(payload))
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn forgets_oldest_profiles_when_full() {
+        let cache = IdentifyCache::new(2);
+
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+        cache.insert("a".to_string(), 3);
+        cache.insert("c".to_string(), 4);
+
+        assert!(cache.is_unchanged("a", 3));
+        assert!(!cache.is_unchanged("a", 1));
+        assert!(!cache.is_unchanged("b", 2));
+        assert!(cache.is_unchanged("c", 4));
+    }
+
+    #[test]
+    fn hash_ignores_property_order() {
+        let first = json!({"properties": {"a": "1", "b": "2"}});
+        let second = json!({"properties": {"b": "2", "a": "1"}});
+
+        assert_eq!(hash_payload(&first), hash_payload(&second));
+        assert_ne!(
+            hash_payload(&first),
+            hash_payload(&json!({"properties": {"a": "1"}}))
+        );
+    }
+}
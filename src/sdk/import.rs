@@ -0,0 +1,80 @@
+//! Import of previously captured events from NDJSON files
+
+use crate::sdk::Tracker;
+use crate::{TrackerError, TrackerResult};
+use std::path::Path;
+use tokio::io::AsyncBufReadExt;
+
+/// Counts of lines handled by [Tracker::import_ndjson]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Events accepted by OpenPanel
+    pub sent: usize,
+    /// Events which couldn't be sent or were rejected by OpenPanel
+    pub failed: usize,
+    /// Lines which aren't a JSON object
+    pub skipped: usize,
+}
+
+impl Tracker {
+    /// Send previously captured events from an NDJSON file, e.g. after an outage or for a
+    /// migration. Each line is an event payload like `{"type": "track", "payload": {...}}`,
+    /// which is sent as is, so embedded timestamps are preserved. Blank lines are ignored.
+    ///
+    /// Events are sent one after another, applying the retry policy if set. Fails only if the
+    /// file can't be read or the tracker is disabled.
+    pub async fn import_ndjson(&self, path: impl AsRef<Path>) -> TrackerResult<ImportReport> {
+        if !self.is_enabled() {
+            return Err(TrackerError::Disabled);
+        }
+
+        let file = tokio::fs::File::open(path).await?;
+        let mut lines = tokio::io::BufReader::new(file).lines();
+        let mut report = ImportReport::default();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let payload = match serde_json::from_str::<serde_json::Value>(&line) {
+                Ok(payload) if payload.is_object() => payload,
+                _ => {
+                    tracing::warn!("Skipping malformed line in NDJSON import");
+                    report.skipped += 1;
+                    continue;
+                }
+            };
+
+            match self.send_request(payload).await {
+                Ok(response) if response.status().is_success() => report.sent += 1,
+                Ok(response) => {
+                    tracing::warn!("Importing event failed with status {}", response.status());
+                    report.failed += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Error importing event: {}", e);
+                    report.failed += 1;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(all(test, not(feature = "disabled-at-compile-time")))]
+mod tests {
+    use super::*;
+    use crate::sdk::testing::MockTransport;
+
+    #[tokio::test]
+    async fn cannot_import_missing_file() -> anyhow::Result<()> {
+        let tracker = MockTransport::new().tracker();
+        let result = tracker.import_ndjson("does/not/exist.ndjson").await;
+
+        assert!(matches!(result, Err(TrackerError::Io(_))));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+//! `tracing` layer mirroring spans and events into OpenPanel events
+
+use crate::sdk::sender::{EventSender, TrackEvent};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::time::Instant;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Field used as profile id instead of a property
+pub const PROFILE_ID_FIELD: &str = "profile_id";
+/// Property name of the span duration in milliseconds
+pub const DURATION_PROPERTY: &str = "duration_ms";
+
+/// Layer converting spans and events matching a filter into `track` calls.
+///
+/// Only spans and events for which the filter returns true are sent, nothing is sent by
+/// default. Spans are sent when they close, named after the span and with the time elapsed on
+/// the tracker's [crate::sdk::clock::Clock] as [DURATION_PROPERTY]. Events are named after their `message` field. All other fields become
+/// properties, except [PROFILE_ID_FIELD] which is used as profile id. Events of this crate are
+/// never sent, to avoid feedback loops.
+///
+/// Events are enqueued into an [EventSender] without waiting. If the queue is full, the event
+/// is dropped.
+pub struct OpenPanelLayer {
+    sender: EventSender,
+    filter: Box<dyn Fn(&Metadata<'_>) -> bool + Send + Sync>,
+}
+
+impl OpenPanelLayer {
+    /// Create layer sending all spans and events for which `filter` returns true
+    pub fn new(
+        sender: EventSender,
+        filter: impl Fn(&Metadata<'_>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            sender,
+            filter: Box::new(filter),
+        }
+    }
+
+    /// Create layer sending spans and events whose target starts with `prefix`,
+    /// e.g. `my_app::analytics`
+    pub fn with_target_prefix(sender: EventSender, prefix: &'static str) -> Self {
+        Self::new(sender, move |metadata| {
+            metadata.target().starts_with(prefix)
+        })
+    }
+
+    fn is_enabled(&self, metadata: &Metadata<'_>) -> bool {
+        !metadata.target().starts_with(env!("CARGO_CRATE_NAME")) && (self.filter)(metadata)
+    }
+
+    fn send(&self, name: String, mut fields: FieldVisitor) {
+        let mut event = TrackEvent::new(name).with_properties(fields.properties.clone());
+
+        if let Some(profile_id) = fields.profile_id.take() {
+            event = event.with_profile_id(profile_id);
+        }
+
+        if let Err(e) = self.sender.try_send(event) {
+            // events of this crate are skipped by `is_enabled`, so the layer ignores its warning
+            tracing::warn!("Error sending span to OpenPanel: {}", e);
+        }
+    }
+}
+
+impl Debug for OpenPanelLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenPanelLayer")
+            .field("sender", &self.sender)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Data stored in the extensions of tracked spans
+struct SpanData {
+    fields: FieldVisitor,
+    started: Instant,
+}
+
+/// Collects fields into properties
+#[derive(Default)]
+struct FieldVisitor {
+    properties: HashMap<String, String>,
+    profile_id: Option<String>,
+    message: Option<String>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: String) {
+        match field.name() {
+            PROFILE_ID_FIELD => self.profile_id = Some(value),
+            "message" => self.message = Some(value),
+            name => {
+                self.properties.insert(name.to_string(), value);
+            }
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.insert(field, format!("{:?}", value));
+    }
+}
+
+impl<S> tracing_subscriber::Layer<S> for OpenPanelLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !self.is_enabled(attrs.metadata()) {
+            return;
+        }
+
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = FieldVisitor::default();
+
+        attrs.record(&mut fields);
+        span.extensions_mut().insert(SpanData {
+            fields,
+            started: self.sender.clock.now(),
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(data) = span.extensions_mut().get_mut::<SpanData>() {
+                values.record(&mut data.fields);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if !self.is_enabled(event.metadata()) {
+            return;
+        }
+
+        let mut fields = FieldVisitor::default();
+
+        event.record(&mut fields);
+
+        let name = fields
+            .message
+            .take()
+            .unwrap_or_else(|| event.metadata().name().to_string());
+
+        self.send(name, fields);
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(mut data) = span.extensions_mut().remove::<SpanData>() else {
+            return;
+        };
+
+        data.fields.properties.insert(
+            DURATION_PROPERTY.to_string(),
+            self.sender
+                .clock
+                .now()
+                .saturating_duration_since(data.started)
+                .as_millis()
+                .to_string(),
+        );
+
+        self.send(span.name().to_string(), data.fields);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::builder::TrackerBuilder;
+    use tokio_stream::StreamExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[tokio::test]
+    async fn can_send_matching_spans_and_events() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default().build()?;
+        let (sender, _handle) = tracker.into_sender();
+        let layer = OpenPanelLayer::new(sender.clone(), |metadata| {
+            metadata.name() == "checkout" || metadata.fields().field("analytics").is_some()
+        });
+        let subscriber = tracing_subscriber::registry().with(layer);
+        let results = sender.results_stream();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(
+                target: "my_app",
+                "checkout",
+                profile_id = "rust_123",
+                items = 3
+            );
+            let _entered = span.enter();
+
+            tracing::info!(target: "my_app", analytics = true, "clicked_buy");
+            tracing::info!(target: "my_app", "not sent");
+        });
+
+        let report = sender.flush_with_report().await?;
+        let events: Vec<String> = results.take(2).map(|result| result.event).collect().await;
+
+        // the tracker is disabled, so both events are dropped after leaving the layer
+        assert_eq!(report.dropped, 2);
+        assert_eq!(events, vec!["clicked_buy", "checkout"]);
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[tokio::test]
+    async fn span_durations_follow_the_tracker_clock() -> anyhow::Result<()> {
+        use crate::sdk::clock::ManualClock;
+        use crate::sdk::testing::MockTransport;
+        use std::time::Duration;
+
+        let clock = ManualClock::default();
+        let transport = MockTransport::new();
+        let (sender, _handle) = transport.tracker().with_clock(clock.clone()).into_sender();
+        let layer = OpenPanelLayer::with_target_prefix(sender.clone(), "my_app");
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(target: "my_app", "checkout");
+
+            clock.advance(Duration::from_millis(1500));
+            drop(span);
+        });
+
+        sender.flush_with_report().await?;
+
+        let payload = transport.assert_tracked("checkout");
+
+        assert_eq!(payload["payload"]["properties"][DURATION_PROPERTY], "1500");
+
+        Ok(())
+    }
+}
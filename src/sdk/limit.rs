@@ -0,0 +1,104 @@
+//! Limit of the length of string property values
+
+use crate::sdk::property::PropertyValue;
+use std::collections::HashMap;
+
+/// Marker replacing a string value longer than the limit set via
+/// [crate::sdk::Tracker::with_max_value_len], e.g. `[truncated:12345 bytes]`
+pub fn truncated_marker(len: usize) -> String {
+    format!("[truncated:{} bytes]", len)
+}
+
+/// Property value whose strings can be limited in length
+pub(crate) trait LimitValue {
+    /// Replace strings longer than `max_len` bytes with [truncated_marker]
+    fn limit(&mut self, key: &str, max_len: usize);
+}
+
+impl LimitValue for String {
+    fn limit(&mut self, key: &str, max_len: usize) {
+        if self.len() > max_len {
+            tracing::warn!(
+                "Property {} exceeds {} bytes, replacing {} bytes with a marker",
+                key,
+                max_len,
+                self.len()
+            );
+            *self = truncated_marker(self.len());
+        }
+    }
+}
+
+impl LimitValue for PropertyValue {
+    fn limit(&mut self, key: &str, max_len: usize) {
+        match self {
+            PropertyValue::String(value) => value.limit(key, max_len),
+            PropertyValue::List(values) => values
+                .iter_mut()
+                .for_each(|value| value.limit(key, max_len)),
+            PropertyValue::Object(values) => values
+                .values_mut()
+                .for_each(|value| value.limit(key, max_len)),
+            _ => {}
+        }
+    }
+}
+
+impl LimitValue for serde_json::Value {
+    fn limit(&mut self, key: &str, max_len: usize) {
+        match self {
+            serde_json::Value::String(value) => value.limit(key, max_len),
+            serde_json::Value::Array(values) => values
+                .iter_mut()
+                .for_each(|value| value.limit(key, max_len)),
+            serde_json::Value::Object(values) => values
+                .values_mut()
+                .for_each(|value| value.limit(key, max_len)),
+            _ => {}
+        }
+    }
+}
+
+/// Limit all values of the given properties
+pub(crate) fn limit_values<V: LimitValue>(properties: &mut HashMap<String, V>, max_len: usize) {
+    for (key, value) in properties.iter_mut() {
+        value.limit(key, max_len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_replace_oversized_values() {
+        let mut properties = HashMap::from([
+            ("screenshot".to_string(), "a".repeat(12345)),
+            ("name".to_string(), "rust".to_string()),
+        ]);
+
+        limit_values(&mut properties, 100);
+
+        assert_eq!(properties["screenshot"], "[truncated:12345 bytes]");
+        assert_eq!(properties["name"], "rust");
+    }
+
+    #[test]
+    fn can_limit_nested_values() {
+        let mut properties = HashMap::from([(
+            "tags".to_string(),
+            PropertyValue::List(vec!["short".into(), "long value".into(), 42i64.into()]),
+        )]);
+
+        limit_values(&mut properties, 5);
+
+        assert_eq!(
+            properties["tags"],
+            PropertyValue::List(vec![
+                "short".into(),
+                "[truncated:10 bytes]".into(),
+                42i64.into()
+            ])
+        );
+    }
+}
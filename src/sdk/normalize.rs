@@ -0,0 +1,132 @@
+//! Normalization of property keys
+
+use std::collections::HashMap;
+
+/// Case property keys are converted to before sending, set via
+/// [crate::sdk::Tracker::with_key_normalization]. Only keys are transformed, values are
+/// sent unchanged. Leading underscores of reserved keys like `__revenue` are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// Convert keys to camelCase, e.g. `user_name` to `userName`
+    CamelCase,
+    /// Convert keys to snake_case, e.g. `userName` to `user_name`. Keys already in
+    /// snake_case pass through unchanged.
+    SnakeCase,
+}
+
+impl Normalization {
+    /// Convert a single key
+    pub fn apply(&self, key: &str) -> String {
+        let name = key.trim_start_matches('_');
+        let prefix = &key[..key.len() - name.len()];
+
+        let name = match self {
+            Normalization::CamelCase => to_camel_case(name),
+            Normalization::SnakeCase => to_snake_case(name),
+        };
+
+        format!("{}{}", prefix, name)
+    }
+
+    /// Convert all keys of the given properties
+    pub(crate) fn apply_keys<V>(&self, properties: HashMap<String, V>) -> HashMap<String, V> {
+        properties
+            .into_iter()
+            .map(|(key, value)| (self.apply(&key), value))
+            .collect()
+    }
+}
+
+fn to_camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+
+    for (i, part) in name.split('_').filter(|part| !part.is_empty()).enumerate() {
+        let mut chars = part.chars();
+
+        if i == 0 {
+            result.push_str(part);
+        } else if let Some(first) = chars.next() {
+            result.extend(first.to_uppercase());
+            result.push_str(chars.as_str());
+        }
+    }
+
+    result
+}
+
+fn to_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut result = String::with_capacity(name.len() + 4);
+
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let previous = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            let starts_word = previous.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit())
+                || (previous.is_some_and(char::is_uppercase)
+                    && next.is_some_and(|n| n.is_lowercase()));
+
+            if starts_word && !result.ends_with('_') {
+                result.push('_');
+            }
+
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(*c);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_convert_to_camel_case() {
+        let cases = [
+            ("user_name", "userName"),
+            ("userName", "userName"),
+            ("page_URL", "pageURL"),
+            ("screen_view_count", "screenViewCount"),
+            ("trailing_", "trailing"),
+            ("double__underscore", "doubleUnderscore"),
+            ("__revenue", "__revenue"),
+            ("__device_id", "__deviceId"),
+        ];
+
+        for (key, expected) in cases {
+            assert_eq!(Normalization::CamelCase.apply(key), expected, "{}", key);
+        }
+    }
+
+    #[test]
+    fn can_convert_to_snake_case() {
+        let cases = [
+            ("userName", "user_name"),
+            ("user_name", "user_name"),
+            ("pageURL", "page_url"),
+            ("HTTPStatus", "http_status"),
+            ("Mixed_CaseKey", "mixed_case_key"),
+            ("step2Done", "step2_done"),
+            ("__revenue", "__revenue"),
+            ("__deviceId", "__device_id"),
+        ];
+
+        for (key, expected) in cases {
+            assert_eq!(Normalization::SnakeCase.apply(key), expected, "{}", key);
+        }
+    }
+
+    #[test]
+    fn values_are_not_transformed() {
+        let properties = HashMap::from([("user_name".to_string(), "some_value".to_string())]);
+        let properties = Normalization::CamelCase.apply_keys(properties);
+
+        assert_eq!(
+            properties,
+            HashMap::from([("userName".to_string(), "some_value".to_string())])
+        );
+    }
+}
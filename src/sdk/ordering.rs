@@ -0,0 +1,121 @@
+//! Per-key queues keeping the call order of events
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// Tail of each key's queue: the generation of the last ticket and its completion
+type Tails = Mutex<HashMap<String, (u64, oneshot::Receiver<()>)>>;
+
+/// Serialized queues per session or profile, set via
+/// [crate::sdk::Tracker::with_ordered_delivery]
+#[derive(Debug, Default)]
+pub(crate) struct OrderedQueues {
+    tails: Arc<Tails>,
+    generation: Mutex<u64>,
+}
+
+impl OrderedQueues {
+    /// Take a place in the key's queue. Tickets are ordered by the time this is called,
+    /// not by the time they are awaited.
+    pub(crate) fn ticket(&self, key: &str) -> Ticket {
+        let generation = {
+            let mut generation = self.generation.lock().unwrap();
+
+            *generation += 1;
+            *generation
+        };
+        let (done, rx) = oneshot::channel();
+        let previous = self
+            .tails
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), (generation, rx))
+            .map(|(_, previous)| previous);
+
+        Ticket {
+            key: key.to_string(),
+            generation,
+            previous,
+            _done: done,
+            tails: Arc::clone(&self.tails),
+        }
+    }
+}
+
+/// Place in a queue. The next ticket of the same key proceeds once this one is dropped.
+#[derive(Debug)]
+pub(crate) struct Ticket {
+    key: String,
+    generation: u64,
+    previous: Option<oneshot::Receiver<()>>,
+    _done: oneshot::Sender<()>,
+    tails: Arc<Tails>,
+}
+
+impl Ticket {
+    /// Wait until all earlier tickets of the key are dropped
+    pub(crate) async fn wait(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            // the sender is dropped, never sent, once the previous ticket is done
+            let _ = previous.await;
+        }
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        let mut tails = self.tails.lock().unwrap();
+
+        // remove the queue once its last ticket is done
+        if tails
+            .get(&self.key)
+            .is_some_and(|(generation, _)| *generation == self.generation)
+        {
+            tails.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn tickets_proceed_in_call_order() {
+        let queues = OrderedQueues::default();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut handles = Vec::new();
+
+        for i in 0..5 {
+            let mut ticket = queues.ticket("session");
+            let order = Arc::clone(&order);
+
+            handles.push(tokio::spawn(async move {
+                ticket.wait().await;
+                // later tickets would overtake without the queue
+                tokio::time::sleep(Duration::from_millis(5 * (5 - i))).await;
+                order.lock().unwrap().push(i);
+            }));
+        }
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+        assert!(queues.tails.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn different_keys_dont_wait() {
+        let queues = OrderedQueues::default();
+        let _first = queues.ticket("first");
+        let mut second = queues.ticket("second");
+
+        tokio::time::timeout(Duration::from_millis(100), second.wait())
+            .await
+            .unwrap();
+    }
+}
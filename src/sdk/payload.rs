@@ -0,0 +1,447 @@
+//! Builders for the `{"type": ..., "payload": ...}` envelope sent to OpenPanel
+
+use crate::sdk::TrackType;
+use crate::sdk::group::IdentifyGroup;
+use crate::sdk::user::IdentifyUser;
+use crate::{TrackerError, TrackerResult, ValidationIssue};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+/// Default key of the type discriminator in the envelope
+pub const DEFAULT_TYPE_FIELD: &str = "type";
+/// Default key of the profile id in the payload, as expected by OpenPanel
+pub const DEFAULT_PROFILE_ID_KEY: &str = "profileId";
+/// Property name of the device id, as expected by OpenPanel
+pub const DEVICE_ID_PROPERTY: &str = "__deviceId";
+
+/// Casing of the type discriminator, set via [crate::sdk::Tracker::with_type_casing]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TypeCasing {
+    /// e.g. `track`, as expected by OpenPanel
+    #[default]
+    Lowercase,
+    /// e.g. `TRACK`
+    Uppercase,
+    /// e.g. `Track`
+    PascalCase,
+}
+
+impl TypeCasing {
+    /// Convert the lowercase type name
+    pub fn apply(&self, name: &str) -> String {
+        match self {
+            TypeCasing::Lowercase => name.to_lowercase(),
+            TypeCasing::Uppercase => name.to_uppercase(),
+            TypeCasing::PascalCase => {
+                let mut chars = name.chars();
+
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// Identifier attributed to `track` events if both a profile id and a device id are
+/// available, set via [crate::sdk::Tracker::with_identifier_preference]. If only one of them
+/// is available, it's sent regardless of the preference.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdentifierPreference {
+    /// Send the profile id only, e.g. after login
+    #[default]
+    Profile,
+    /// Send the device id only, e.g. before login
+    Device,
+    /// Send both, letting OpenPanel link the device to the profile during the login transition
+    Both,
+}
+
+/// Select the identifiers of a `track` payload. The device id is sent as [DEVICE_ID_PROPERTY]
+/// property; if `device_id` is `None`, a device id already in the properties is used.
+/// Other payload types are returned unchanged.
+pub fn with_identifiers(
+    mut payload: Value,
+    device_id: Option<&str>,
+    preference: IdentifierPreference,
+) -> Value {
+    if payload.get(DEFAULT_TYPE_FIELD) != Some(&json!(TrackType::Track)) {
+        return payload;
+    }
+
+    let Some(body) = payload.get_mut("payload").and_then(Value::as_object_mut) else {
+        return payload;
+    };
+
+    let has_profile = body.get("profileId").is_some_and(|id| !id.is_null());
+    let Some(properties) = body.get_mut("properties").and_then(Value::as_object_mut) else {
+        return payload;
+    };
+    let existing = properties.remove(DEVICE_ID_PROPERTY);
+    let Some(device_id) = device_id
+        .map(|id| Value::String(id.to_string()))
+        .or(existing)
+    else {
+        return payload;
+    };
+
+    if preference == IdentifierPreference::Profile && has_profile {
+        return payload;
+    }
+
+    properties.insert(DEVICE_ID_PROPERTY.to_string(), device_id);
+
+    if preference == IdentifierPreference::Device {
+        body.insert("profileId".to_string(), Value::Null);
+    }
+
+    payload
+}
+
+/// Move the type discriminator of an envelope to the given key and casing
+pub fn with_type_field(mut payload: Value, field: &str, casing: TypeCasing) -> Value {
+    if let Some(envelope) = payload.as_object_mut() {
+        if let Some(Value::String(name)) = envelope.remove(DEFAULT_TYPE_FIELD) {
+            envelope.insert(field.to_string(), Value::String(casing.apply(&name)));
+        }
+    }
+
+    payload
+}
+
+/// Move the profile id of an envelope's payload to the given key
+pub fn with_profile_id_key(mut payload: Value, key: &str) -> Value {
+    if let Some(body) = payload.get_mut("payload").and_then(Value::as_object_mut) {
+        if let Some(profile_id) = body.remove(DEFAULT_PROFILE_ID_KEY) {
+            body.insert(key.to_string(), profile_id);
+        }
+    }
+
+    payload
+}
+
+/// Create payload for a `track` event
+pub fn track<P: Serialize>(event: &str, profile_id: Option<&str>, properties: &P) -> Value {
+    json!({
+        "type": TrackType::Track,
+        "payload": {
+            "profileId": profile_id,
+            "name": event,
+            "properties": properties
+        }
+    })
+}
+
+/// Create payload for an `identify` call
+pub fn identify(user: &IdentifyUser) -> Value {
+    json!({
+        "type": TrackType::Identify,
+        "payload": user
+    })
+}
+
+/// Create payload for an `identify_group` call
+pub fn identify_group(group: &IdentifyGroup) -> Value {
+    json!({
+        "type": TrackType::Group,
+        "payload": group
+    })
+}
+
+/// Create payload for an `increment` call
+pub fn increment(profile_id: &str, property: &str, value: i64) -> Value {
+    counter(TrackType::Increment, profile_id, property, value)
+}
+
+/// Create payload for a `decrement` call
+pub fn decrement(profile_id: &str, property: &str, value: i64) -> Value {
+    counter(TrackType::Decrement, profile_id, property, value)
+}
+
+/// Check that the payload matches the structure OpenPanel expects for its `type`.
+/// Returns [TrackerError::Validation] listing all missing or invalid fields.
+pub fn validate(payload: &Value) -> TrackerResult<()> {
+    let issues = validation_issues(payload);
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(TrackerError::Validation(issues))
+    }
+}
+
+/// All missing or invalid fields of the payload, see [validate]
+pub(crate) fn validation_issues(payload: &Value) -> Vec<ValidationIssue> {
+    let mut errors = Vec::new();
+    let body = payload.get("payload");
+
+    if !body.is_some_and(Value::is_object) {
+        errors.push(ValidationIssue::new("payload", "must be an object"));
+    }
+
+    let body = body.cloned().unwrap_or_default();
+    let mut check =
+        |field: &str, valid: fn(&Value) -> bool, required: bool, expected: &str| match body
+            .get(field)
+        {
+            None | Some(Value::Null) if !required => {}
+            None | Some(Value::Null) => errors.push(ValidationIssue::new(
+                format!("payload.{}", field),
+                "is missing",
+            )),
+            Some(value) if !valid(value) => errors.push(ValidationIssue::new(
+                format!("payload.{}", field),
+                format!("must be {}", expected),
+            )),
+            Some(_) => {}
+        };
+    let string = |value: &Value| value.as_str().is_some_and(|s| !s.is_empty());
+
+    match payload.get("type").and_then(Value::as_str) {
+        Some("track") => {
+            check("name", string, true, "a non-empty string");
+            check("profileId", string, false, "a non-empty string");
+            check("properties", Value::is_object, false, "an object");
+        }
+        Some("identify") => {
+            check("profileId", string, true, "a non-empty string");
+            check("email", Value::is_string, false, "a string");
+            check("firstName", Value::is_string, false, "a string");
+            check("lastName", Value::is_string, false, "a string");
+            check("properties", Value::is_object, false, "an object");
+        }
+        Some("increment" | "decrement") => {
+            check("profileId", string, true, "a non-empty string");
+            check("property", string, true, "a non-empty string");
+            check("value", Value::is_i64, true, "an integer");
+        }
+        Some("group") => {
+            check("groupId", string, true, "a non-empty string");
+            check("name", Value::is_string, false, "a string");
+            check("properties", Value::is_object, false, "an object");
+        }
+        Some(other) => errors.push(ValidationIssue::new(
+            "type",
+            format!("must be a known type, got {}", other),
+        )),
+        None => errors.push(ValidationIssue::new("type", "is missing")),
+    }
+
+    errors
+}
+
+fn counter(track_type: TrackType, profile_id: &str, property: &str, value: i64) -> Value {
+    json!({
+        "type": track_type,
+        "payload": {
+            "profileId": profile_id,
+            "property": property,
+            "value": value
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn can_create_track_payload() {
+        let properties = HashMap::from([("name".to_string(), "rust".to_string())]);
+
+        assert_eq!(
+            track("test_event", Some("rust_123"), &properties),
+            json!({
+                "type": "track",
+                "payload": {
+                    "profileId": "rust_123",
+                    "name": "test_event",
+                    "properties": { "name": "rust" }
+                }
+            })
+        );
+        assert_eq!(
+            track("test_event", None, &properties)["payload"]["profileId"],
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn can_create_identify_payload() {
+        let user = IdentifyUser {
+            profile_id: "rust_123".to_string(),
+            email: "rust@test.com".to_string(),
+            first_name: "Rust".to_string(),
+            last_name: "Tester".to_string(),
+            properties: HashMap::new(),
+        };
+
+        assert_eq!(
+            identify(&user),
+            json!({
+                "type": "identify",
+                "payload": {
+                    "profileId": "rust_123",
+                    "email": "rust@test.com",
+                    "firstName": "Rust",
+                    "lastName": "Tester",
+                    "properties": {}
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn can_create_identify_group_payload() {
+        let group = IdentifyGroup {
+            group_id: "acme".to_string(),
+            name: "Acme Inc.".to_string(),
+            properties: HashMap::from([("employees".to_string(), "250".to_string())]),
+        };
+
+        assert_eq!(
+            identify_group(&group),
+            json!({
+                "type": "group",
+                "payload": {
+                    "groupId": "acme",
+                    "name": "Acme Inc.",
+                    "properties": { "employees": "250" }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn can_change_type_field() {
+        let payload = track("test_event", None, &HashMap::<String, String>::new());
+
+        assert_eq!(
+            with_type_field(payload.clone(), DEFAULT_TYPE_FIELD, TypeCasing::Lowercase),
+            payload
+        );
+
+        let payload = with_type_field(payload, "kind", TypeCasing::PascalCase);
+
+        assert_eq!(payload["kind"], "Track");
+        assert!(payload.get("type").is_none());
+        assert_eq!(TypeCasing::Uppercase.apply("identify"), "IDENTIFY");
+    }
+
+    #[test]
+    fn can_change_profile_id_key() {
+        let payload = with_profile_id_key(increment("rust_123", "visits", 1), "profile_id");
+
+        assert_eq!(payload["payload"]["profile_id"], "rust_123");
+        assert!(payload["payload"].get(DEFAULT_PROFILE_ID_KEY).is_none());
+        assert_eq!(payload["payload"]["property"], "visits");
+    }
+
+    #[test]
+    fn can_select_identifiers() {
+        let payload = track("test_event", Some("rust_123"), &json!({"page": "home"}));
+        let select = |preference| with_identifiers(payload.clone(), Some("device_1"), preference);
+
+        let profile = select(IdentifierPreference::Profile);
+
+        assert_eq!(profile["payload"]["profileId"], "rust_123");
+        assert!(
+            profile["payload"]["properties"]
+                .get(DEVICE_ID_PROPERTY)
+                .is_none()
+        );
+
+        let device = select(IdentifierPreference::Device);
+
+        assert_eq!(device["payload"]["profileId"], Value::Null);
+        assert_eq!(
+            device["payload"]["properties"][DEVICE_ID_PROPERTY],
+            "device_1"
+        );
+
+        let both = select(IdentifierPreference::Both);
+
+        assert_eq!(both["payload"]["profileId"], "rust_123");
+        assert_eq!(
+            both["payload"]["properties"][DEVICE_ID_PROPERTY],
+            "device_1"
+        );
+        assert_eq!(both["payload"]["properties"]["page"], "home");
+    }
+
+    #[test]
+    fn available_identifier_is_sent_regardless_of_preference() {
+        let anonymous = track(
+            "test_event",
+            None,
+            &json!({ DEVICE_ID_PROPERTY: "device_1" }),
+        );
+        let payload = with_identifiers(anonymous, None, IdentifierPreference::Profile);
+
+        assert_eq!(
+            payload["payload"]["properties"][DEVICE_ID_PROPERTY],
+            "device_1"
+        );
+
+        let identified = track("test_event", Some("rust_123"), &json!({}));
+        let payload = with_identifiers(identified.clone(), None, IdentifierPreference::Device);
+
+        assert_eq!(payload, identified);
+        assert_eq!(
+            with_identifiers(
+                increment("rust_123", "visits", 1),
+                Some("device_1"),
+                IdentifierPreference::Device
+            ),
+            increment("rust_123", "visits", 1)
+        );
+    }
+
+    #[test]
+    fn can_validate_payloads() {
+        let properties = HashMap::from([("name".to_string(), "rust".to_string())]);
+
+        assert!(validate(&track("test_event", Some("rust_123"), &properties)).is_ok());
+        assert!(validate(&increment("rust_123", "visits", 1)).is_ok());
+
+        let result = validate(&json!({
+            "type": "increment",
+            "payload": { "profileId": "", "value": "1" }
+        }));
+
+        assert!(matches!(
+            result,
+            Err(TrackerError::Validation(issues)) if issues == vec![
+                ValidationIssue::new("payload.profileId", "must be a non-empty string"),
+                ValidationIssue::new("payload.property", "is missing"),
+                ValidationIssue::new("payload.value", "must be an integer"),
+            ]
+        ));
+        assert_eq!(
+            validation_issues(&json!({ "type": "alias" })),
+            vec![
+                ValidationIssue::new("payload", "must be an object"),
+                ValidationIssue::new("type", "must be a known type, got alias"),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_create_counter_payloads() {
+        let expected = |track_type: &str| {
+            json!({
+                "type": track_type,
+                "payload": {
+                    "profileId": "rust_123",
+                    "property": "visits",
+                    "value": 1
+                }
+            })
+        };
+
+        assert_eq!(increment("rust_123", "visits", 1), expected("increment"));
+        assert_eq!(decrement("rust_123", "visits", 1), expected("decrement"));
+    }
+}
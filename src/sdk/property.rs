@@ -0,0 +1,546 @@
+//! Property values used for events with typed properties
+
+use crate::{TrackerError, TrackerResult};
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Default suffix of keys with [PropertyValue::Duration] values, see
+/// [crate::sdk::Tracker::with_duration_suffix]
+pub const DEFAULT_DURATION_SUFFIX: &str = "_ms";
+
+/// Typed property value. Values are serialized as native JSON values, i.e. lists become
+/// JSON arrays, nested maps JSON objects and unit enums serialized via
+/// [PropertyValue::from_serialize] become strings.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum PropertyValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+    List(Vec<PropertyValue>),
+    /// Nested object, e.g. `{"device": {"os": "iOS"}}`, see also [nest_dotted_keys]
+    Object(HashMap<String, PropertyValue>),
+    /// Serialized as whole milliseconds, so durations are uniform across events
+    #[serde(serialize_with = "serialize_millis")]
+    Duration(Duration),
+}
+
+fn serialize_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Convert the fields of a serializable struct into string properties. Strings are kept,
+/// other values are sent as JSON, e.g. `42` or `["a","b"]`, and `null` fields are skipped.
+/// Returns [TrackerError::UnsupportedPropertyValue] if the value isn't serialized as an object.
+pub(crate) fn context_properties<C: Serialize + ?Sized>(
+    context: &C,
+) -> TrackerResult<HashMap<String, String>> {
+    match serde_json::to_value(context)? {
+        serde_json::Value::Object(fields) => Ok(fields
+            .into_iter()
+            .filter(|(_, value)| !value.is_null())
+            .map(|(key, value)| match value {
+                serde_json::Value::String(value) => (key, value),
+                value => (key, value.to_string()),
+            })
+            .collect()),
+        value => Err(TrackerError::UnsupportedPropertyValue(value.to_string())),
+    }
+}
+
+/// Group dotted keys into nested objects, e.g. `device.os` and `device.model` become a
+/// `device` object with `os` and `model` fields. A dotted key whose prefix is taken by a
+/// value other than an object is kept as is.
+pub fn nest_dotted_keys(
+    properties: HashMap<String, PropertyValue>,
+) -> HashMap<String, PropertyValue> {
+    let (mut dotted, nested): (Vec<_>, Vec<_>) = properties
+        .into_iter()
+        .partition(|(key, _)| key.contains('.'));
+    let mut nested: HashMap<_, _> = nested.into_iter().collect();
+
+    // insert in a stable order, so conflicts are resolved the same way every time
+    dotted.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (key, value) in dotted {
+        if let Err(value) = insert_nested(&mut nested, &key, value) {
+            nested.insert(key, value);
+        }
+    }
+
+    nested
+}
+
+/// Insert the value at the dotted path, returning it if the path is taken by another value
+fn insert_nested(
+    properties: &mut HashMap<String, PropertyValue>,
+    path: &str,
+    value: PropertyValue,
+) -> Result<(), PropertyValue> {
+    match path.split_once('.') {
+        None if properties.contains_key(path) => Err(value),
+        None => {
+            properties.insert(path.to_string(), value);
+            Ok(())
+        }
+        Some((head, rest)) => match properties
+            .entry(head.to_string())
+            .or_insert_with(|| PropertyValue::Object(HashMap::new()))
+        {
+            PropertyValue::Object(fields) => insert_nested(fields, rest, value),
+            _ => Err(value),
+        },
+    }
+}
+
+/// Append the suffix to keys of duration values which don't end with it yet, e.g. `load_time`
+/// becomes `load_time_ms`
+pub(crate) fn suffix_duration_keys(
+    properties: HashMap<String, PropertyValue>,
+    suffix: &str,
+) -> HashMap<String, PropertyValue> {
+    properties
+        .into_iter()
+        .map(|(key, value)| match value {
+            PropertyValue::Duration(_) if !key.ends_with(suffix) => {
+                (format!("{}{}", key, suffix), value)
+            }
+            value => (key, value),
+        })
+        .collect()
+}
+
+impl PropertyValue {
+    /// Convert any serializable value, e.g. an enum deriving `Serialize`, into a property value.
+    /// Structs and maps become [PropertyValue::Object]s, `null` is not supported.
+    pub fn from_serialize<T: Serialize>(value: &T) -> TrackerResult<Self> {
+        Self::try_from(serde_json::to_value(value)?)
+    }
+}
+
+impl TryFrom<serde_json::Value> for PropertyValue {
+    type Error = TrackerError;
+
+    fn try_from(value: serde_json::Value) -> TrackerResult<Self> {
+        match value {
+            serde_json::Value::String(value) => Ok(Self::String(value)),
+            serde_json::Value::Bool(value) => Ok(Self::Bool(value)),
+            serde_json::Value::Number(value) => match value.as_i64() {
+                Some(value) => Ok(Self::Integer(value)),
+                None => Ok(Self::Float(value.as_f64().unwrap_or_default())),
+            },
+            serde_json::Value::Array(values) => Ok(Self::List(
+                values
+                    .into_iter()
+                    .map(Self::try_from)
+                    .collect::<TrackerResult<_>>()?,
+            )),
+            serde_json::Value::Object(fields) => Ok(Self::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| Ok((key, Self::try_from(value)?)))
+                    .collect::<TrackerResult<_>>()?,
+            )),
+            value => Err(TrackerError::UnsupportedPropertyValue(value.to_string())),
+        }
+    }
+}
+
+/// Conversion of domain types like money, ids or enums into property values, used by the
+/// [crate::properties] macro. Implement it for your own types to avoid stringifying them by hand.
+pub trait IntoProperty {
+    fn into_property(self) -> PropertyValue;
+}
+
+impl IntoProperty for PropertyValue {
+    fn into_property(self) -> PropertyValue {
+        self
+    }
+}
+
+impl IntoProperty for String {
+    fn into_property(self) -> PropertyValue {
+        PropertyValue::String(self)
+    }
+}
+
+impl IntoProperty for &str {
+    fn into_property(self) -> PropertyValue {
+        PropertyValue::String(self.to_string())
+    }
+}
+
+impl IntoProperty for bool {
+    fn into_property(self) -> PropertyValue {
+        PropertyValue::Bool(self)
+    }
+}
+
+impl IntoProperty for Duration {
+    fn into_property(self) -> PropertyValue {
+        PropertyValue::Duration(self)
+    }
+}
+
+impl<T: IntoProperty> IntoProperty for Vec<T> {
+    fn into_property(self) -> PropertyValue {
+        PropertyValue::List(self.into_iter().map(IntoProperty::into_property).collect())
+    }
+}
+
+impl<T: IntoProperty> IntoProperty for HashMap<String, T> {
+    fn into_property(self) -> PropertyValue {
+        PropertyValue::Object(
+            self.into_iter()
+                .map(|(key, value)| (key, value.into_property()))
+                .collect(),
+        )
+    }
+}
+
+/// Integers which always fit into an `i64`
+macro_rules! impl_into_property_for_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoProperty for $ty {
+                fn into_property(self) -> PropertyValue {
+                    PropertyValue::Integer(self.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_into_property_for_integer!(i8, i16, i32, i64, u8, u16, u32);
+
+/// Integers which may exceed `i64`, sent as strings in that case to stay exact
+macro_rules! impl_into_property_for_large_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoProperty for $ty {
+                fn into_property(self) -> PropertyValue {
+                    i64::try_from(self)
+                        .map(PropertyValue::Integer)
+                        .unwrap_or_else(|_| PropertyValue::String(self.to_string()))
+                }
+            }
+        )*
+    };
+}
+
+impl_into_property_for_large_integer!(isize, u64, usize);
+
+impl IntoProperty for f32 {
+    fn into_property(self) -> PropertyValue {
+        PropertyValue::Float(self.into())
+    }
+}
+
+impl IntoProperty for f64 {
+    fn into_property(self) -> PropertyValue {
+        PropertyValue::Float(self)
+    }
+}
+
+/// Sent as RFC 3339 string, e.g. `2026-10-14T12:00:00+00:00`
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> IntoProperty for chrono::DateTime<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    fn into_property(self) -> PropertyValue {
+        PropertyValue::String(self.to_rfc3339())
+    }
+}
+
+/// Sent as ISO 8601 string, e.g. `2026-10-14`
+#[cfg(feature = "chrono")]
+impl IntoProperty for chrono::NaiveDate {
+    fn into_property(self) -> PropertyValue {
+        PropertyValue::String(self.to_string())
+    }
+}
+
+/// Sent as ISO 8601 string without offset, e.g. `2026-10-14T12:00:00`
+#[cfg(feature = "chrono")]
+impl IntoProperty for chrono::NaiveDateTime {
+    fn into_property(self) -> PropertyValue {
+        PropertyValue::String(self.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+    }
+}
+
+/// Create typed properties for `track_with_values`, converting each value via [IntoProperty]
+///
+/// ```rust
+/// use openpanel_sdk::properties;
+/// use std::time::Duration;
+///
+/// let properties = properties! {
+///     "plan" => "pro",
+///     "seats" => 5u32,
+///     "load_time" => Duration::from_millis(1500),
+/// };
+///
+/// assert_eq!(properties.len(), 3);
+/// ```
+#[macro_export]
+macro_rules! properties {
+    ($($key:expr => $value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut properties = ::std::collections::HashMap::<
+            ::std::string::String,
+            $crate::sdk::property::PropertyValue,
+        >::new();
+
+        $(
+            properties.insert(
+                ::std::string::ToString::to_string(&$key),
+                $crate::sdk::property::IntoProperty::into_property($value),
+            );
+        )*
+
+        properties
+    }};
+}
+
+impl From<String> for PropertyValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for PropertyValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<i64> for PropertyValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<f64> for PropertyValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<bool> for PropertyValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<Duration> for PropertyValue {
+    fn from(value: Duration) -> Self {
+        Self::Duration(value)
+    }
+}
+
+impl From<Vec<String>> for PropertyValue {
+    fn from(values: Vec<String>) -> Self {
+        Self::List(values.into_iter().map(Self::String).collect())
+    }
+}
+
+impl From<Vec<PropertyValue>> for PropertyValue {
+    fn from(values: Vec<PropertyValue>) -> Self {
+        Self::List(values)
+    }
+}
+
+impl From<HashMap<String, PropertyValue>> for PropertyValue {
+    fn from(fields: HashMap<String, PropertyValue>) -> Self {
+        Self::Object(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Plan {
+        Free,
+    }
+
+    #[test]
+    fn can_serialize_list_as_array() -> anyhow::Result<()> {
+        let value = PropertyValue::from(vec!["a".to_string(), "b".to_string()]);
+
+        assert_eq!(serde_json::to_value(value)?, json!(["a", "b"]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_serialize_enum_as_string() -> anyhow::Result<()> {
+        let value = PropertyValue::from_serialize(&Plan::Free)?;
+
+        assert_eq!(value, PropertyValue::String("free".to_string()));
+        assert_eq!(serde_json::to_value(value)?, json!("free"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_serialize_nested_objects() -> anyhow::Result<()> {
+        let properties = crate::properties! {
+            "device" => crate::properties! { "os" => "iOS", "version" => 17 },
+        };
+
+        assert_eq!(
+            serde_json::to_value(&properties)?,
+            json!({"device": {"os": "iOS", "version": 17}})
+        );
+        assert_eq!(
+            PropertyValue::from_serialize(&json!({"os": "iOS"}))?,
+            PropertyValue::Object(HashMap::from([("os".to_string(), "iOS".into())]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_nest_dotted_keys() -> anyhow::Result<()> {
+        let properties = crate::properties! {
+            "device.os" => "iOS",
+            "device.model" => "iPhone",
+            "page" => "home",
+            "page.title" => "Home",
+        };
+
+        assert_eq!(
+            serde_json::to_value(nest_dotted_keys(properties))?,
+            json!({
+                "device": {"os": "iOS", "model": "iPhone"},
+                "page": "home",
+                "page.title": "Home"
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_serialize_duration_as_millis() -> anyhow::Result<()> {
+        let value = PropertyValue::from(Duration::from_micros(1_500_900));
+
+        assert_eq!(serde_json::to_value(value)?, json!(1500));
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_suffix_duration_keys() {
+        let properties = HashMap::from([
+            ("load_time".to_string(), Duration::from_secs(1).into()),
+            ("render_ms".to_string(), Duration::from_secs(2).into()),
+            ("page".to_string(), "home".into()),
+        ]);
+        let properties = suffix_duration_keys(properties, DEFAULT_DURATION_SUFFIX);
+        let mut keys = properties.keys().cloned().collect::<Vec<_>>();
+
+        keys.sort();
+
+        assert_eq!(keys, vec!["load_time_ms", "page", "render_ms"]);
+    }
+
+    struct Money {
+        cents: i64,
+        currency: &'static str,
+    }
+
+    impl IntoProperty for Money {
+        fn into_property(self) -> PropertyValue {
+            PropertyValue::String(format!("{} {}", self.cents, self.currency))
+        }
+    }
+
+    #[test]
+    fn can_convert_with_into_property() {
+        let properties = crate::properties! {
+            "price" => Money { cents: 1999, currency: "EUR" },
+            "seats" => 5u8,
+            "views" => u64::MAX,
+            "ratio" => 0.5f32,
+            "tags" => vec!["a", "b"],
+        };
+
+        assert_eq!(
+            properties["price"],
+            PropertyValue::String("1999 EUR".to_string())
+        );
+        assert_eq!(properties["seats"], PropertyValue::Integer(5));
+        assert_eq!(
+            properties["views"],
+            PropertyValue::String(u64::MAX.to_string())
+        );
+        assert_eq!(properties["ratio"], PropertyValue::Float(0.5));
+        assert_eq!(
+            properties["tags"],
+            PropertyValue::from(vec!["a".to_string(), "b".to_string()])
+        );
+        assert!(crate::properties! {}.is_empty());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn can_convert_chrono_dates() {
+        use chrono::{NaiveDate, TimeZone, Utc};
+
+        let date = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let time = Utc.with_ymd_and_hms(2026, 10, 14, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            date.into_property(),
+            PropertyValue::String("2026-10-14".to_string())
+        );
+        assert_eq!(
+            date.and_hms_opt(12, 0, 0).unwrap().into_property(),
+            PropertyValue::String("2026-10-14T12:00:00".to_string())
+        );
+        assert_eq!(
+            time.into_property(),
+            PropertyValue::String("2026-10-14T12:00:00+00:00".to_string())
+        );
+    }
+
+    #[test]
+    fn can_convert_context_into_properties() -> anyhow::Result<()> {
+        #[derive(Serialize)]
+        struct RequestContext {
+            trace_id: &'static str,
+            tenant: u32,
+            locale: Option<&'static str>,
+        }
+
+        let properties = context_properties(&RequestContext {
+            trace_id: "abc",
+            tenant: 42,
+            locale: None,
+        })?;
+
+        assert_eq!(
+            properties,
+            HashMap::from([
+                ("trace_id".to_string(), "abc".to_string()),
+                ("tenant".to_string(), "42".to_string()),
+            ])
+        );
+        assert!(context_properties(&"not an object").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn cannot_convert_null() {
+        assert!(PropertyValue::from_serialize(&Option::<String>::None).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! Rate limit status reported by OpenPanel in response headers
+
+use reqwest::header::HeaderMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Header with the number of requests allowed per window
+pub const LIMIT_HEADER: &str = "x-ratelimit-limit";
+/// Header with the number of requests left in the current window
+pub const REMAINING_HEADER: &str = "x-ratelimit-remaining";
+/// Header with the seconds until the current window resets, or the Unix timestamp of the reset
+pub const RESET_HEADER: &str = "x-ratelimit-reset";
+
+/// Reset values at or above this are read as Unix timestamps instead of seconds
+const UNIX_TIMESTAMP_THRESHOLD: u64 = 1_000_000_000;
+
+/// Rate limit status of the last response carrying rate limit headers, see
+/// [crate::sdk::Tracker::rate_limit_status]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Requests allowed per window, from [LIMIT_HEADER]
+    pub limit: Option<u64>,
+    /// Requests left in the current window, from [REMAINING_HEADER]
+    pub remaining: Option<u64>,
+    /// When the current window resets, from [RESET_HEADER]
+    pub reset_at: Option<Instant>,
+    /// When the response was received
+    pub observed_at: Instant,
+}
+
+impl RateLimitStatus {
+    /// Parse the rate limit headers of a response received at `now`. Returns `None` if the
+    /// response has none of them.
+    pub fn from_headers(headers: &HeaderMap, now: Instant) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+        let limit = header(LIMIT_HEADER);
+        let remaining = header(REMAINING_HEADER);
+        let reset_at = header(RESET_HEADER).map(|reset| now + until_reset(reset));
+
+        if limit.is_none() && remaining.is_none() && reset_at.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            limit,
+            remaining,
+            reset_at,
+            observed_at: now,
+        })
+    }
+
+    /// Whether no requests are left in the current window at `now`
+    pub fn is_exhausted(&self, now: Instant) -> bool {
+        self.remaining == Some(0) && self.reset_at.is_none_or(|reset_at| now < reset_at)
+    }
+}
+
+/// Time until the reset, given in seconds or as Unix timestamp
+fn until_reset(reset: u64) -> Duration {
+    if reset < UNIX_TIMESTAMP_THRESHOLD {
+        return Duration::from_secs(reset);
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    Duration::from_secs(reset).saturating_sub(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn can_parse_rate_limit_headers() {
+        let now = Instant::now();
+        let mut headers = HeaderMap::new();
+
+        assert!(RateLimitStatus::from_headers(&headers, now).is_none());
+
+        headers.insert(LIMIT_HEADER, HeaderValue::from_static("100"));
+        headers.insert(REMAINING_HEADER, HeaderValue::from_static("0"));
+        headers.insert(RESET_HEADER, HeaderValue::from_static("30"));
+
+        let status = RateLimitStatus::from_headers(&headers, now).unwrap();
+
+        assert_eq!(status.limit, Some(100));
+        assert_eq!(status.remaining, Some(0));
+        assert_eq!(status.reset_at, Some(now + Duration::from_secs(30)));
+        assert!(status.is_exhausted(now));
+        assert!(!status.is_exhausted(now + Duration::from_secs(31)));
+    }
+
+    #[test]
+    fn can_parse_reset_timestamp() {
+        let in_a_minute = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 60;
+
+        assert!(until_reset(in_a_minute) <= Duration::from_secs(60));
+        assert!(until_reset(in_a_minute) >= Duration::from_secs(58));
+        assert_eq!(until_reset(UNIX_TIMESTAMP_THRESHOLD), Duration::ZERO);
+    }
+}
@@ -0,0 +1,76 @@
+//! OpenPanel ingest regions
+
+use crate::TrackerResult;
+use crate::sdk::{Tracker, normalize_api_url};
+
+/// Ingest region selected via [Tracker::with_region]
+///
+/// | Region           | API URL                           |
+/// |------------------|-----------------------------------|
+/// | `Eu`             | `https://api.openpanel.dev/track` |
+/// | `Custom(base)`   | `<base>/track`                    |
+///
+/// OpenPanel Cloud currently ingests in the EU only. Use `Custom` for self-hosted instances
+/// or other hosts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Region {
+    /// OpenPanel Cloud, hosted in the EU
+    Eu,
+    /// Base URL of another ingest host, e.g. `https://openpanel.example.com/api`
+    Custom(String),
+}
+
+impl Region {
+    /// API URL events are sent to in this region
+    pub fn api_url(&self) -> String {
+        match self {
+            Region::Eu => "https://api.openpanel.dev/track".to_string(),
+            Region::Custom(base) => format!("{}/track", base.trim_end_matches('/')),
+        }
+    }
+}
+
+impl Tracker {
+    /// Send events to the ingest host of the given region, replacing the API URL. The URL is
+    /// checked like in [Tracker::try_new], returning [crate::TrackerError::InvalidUrl] for a
+    /// custom base URL which isn't an http(s) URL.
+    pub fn with_region(mut self, region: Region) -> TrackerResult<Self> {
+        self.api_url = normalize_api_url(&region.api_url())?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TrackerError;
+    use crate::sdk::builder::TrackerBuilder;
+
+    #[test]
+    fn can_resolve_region_urls() {
+        assert_eq!(Region::Eu.api_url(), "https://api.openpanel.dev/track");
+        assert_eq!(
+            Region::Custom("https://openpanel.example.com/api/".to_string()).api_url(),
+            "https://openpanel.example.com/api/track"
+        );
+    }
+
+    #[test]
+    fn can_set_region() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default()
+            .build()?
+            .with_region(Region::Custom("http://localhost:3333".to_string()))?;
+
+        assert_eq!(tracker.api_url(), "http://localhost:3333/track");
+
+        let tracker = tracker.with_region(Region::Custom("openpanel.example.com".to_string()))?;
+
+        assert_eq!(tracker.api_url(), "https://openpanel.example.com/track");
+        assert!(matches!(
+            tracker.with_region(Region::Custom("ftp://openpanel.example.com".to_string())),
+            Err(TrackerError::InvalidUrl(_))
+        ));
+
+        Ok(())
+    }
+}
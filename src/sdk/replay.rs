@@ -0,0 +1,194 @@
+//! Bounded in-memory buffer of failed events replayed on the next track
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Default time after which buffered events are dropped
+pub const DEFAULT_REPLAY_TTL: Duration = Duration::from_secs(300);
+
+/// Failed payloads waiting to be replayed
+#[derive(Debug)]
+pub(crate) struct ReplayBuffer {
+    max_buffered: usize,
+    ttl: Duration,
+    payloads: Mutex<VecDeque<(Instant, serde_json::Value)>>,
+}
+
+impl ReplayBuffer {
+    pub(crate) fn new(max_buffered: usize) -> Self {
+        Self {
+            max_buffered,
+            ttl: DEFAULT_REPLAY_TTL,
+            payloads: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub(crate) fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+    }
+
+    /// Buffer a failed payload at the given time, dropping the oldest one if the buffer is full
+    pub(crate) fn push(&self, payload: serde_json::Value, now: Instant) {
+        if self.max_buffered == 0 {
+            return;
+        }
+
+        let mut payloads = self.payloads.lock().unwrap();
+
+        if payloads.len() >= self.max_buffered {
+            tracing::warn!("Replay buffer full, dropping oldest event");
+            payloads.pop_front();
+        }
+
+        payloads.push_back((now, payload));
+    }
+
+    /// Put payloads which failed again back in front of the buffer, keeping their age
+    pub(crate) fn restore(&self, failed: Vec<(Instant, serde_json::Value)>) {
+        let mut payloads = self.payloads.lock().unwrap();
+
+        for entry in failed.into_iter().rev() {
+            payloads.push_front(entry);
+        }
+
+        payloads.truncate(self.max_buffered);
+    }
+
+    /// Take all buffered payloads which are not expired at the given time, oldest first
+    pub(crate) fn take(&self, now: Instant) -> Vec<(Instant, serde_json::Value)> {
+        let mut payloads = self.payloads.lock().unwrap();
+        let ttl = self.ttl;
+
+        payloads
+            .drain(..)
+            .filter(|(buffered_at, _)| {
+                let expired = now.saturating_duration_since(*buffered_at) > ttl;
+
+                if expired {
+                    tracing::warn!("Dropping expired event from replay buffer");
+                }
+
+                !expired
+            })
+            .collect()
+    }
+
+    /// Take all buffered payloads like [ReplayBuffer::take]. Payloads not confirmed as replayed
+    /// are restored once the returned guard is dropped, also if the replay is cancelled.
+    pub(crate) fn take_pending(&self, now: Instant) -> Pending<'_> {
+        Pending {
+            buffer: self,
+            payloads: self.take(now).into(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.payloads.lock().unwrap().len()
+    }
+}
+
+/// Payloads taken for replay, restored to the buffer on drop unless confirmed
+#[derive(Debug)]
+pub(crate) struct Pending<'a> {
+    buffer: &'a ReplayBuffer,
+    payloads: VecDeque<(Instant, serde_json::Value)>,
+}
+
+impl Pending<'_> {
+    /// Oldest payload not replayed yet
+    pub(crate) fn front(&self) -> Option<&serde_json::Value> {
+        self.payloads.front().map(|(_, payload)| payload)
+    }
+
+    /// Confirm the oldest payload as replayed, so it isn't restored
+    pub(crate) fn confirm(&mut self) {
+        self.payloads.pop_front();
+    }
+}
+
+impl Drop for Pending<'_> {
+    fn drop(&mut self) {
+        if !self.payloads.is_empty() {
+            self.buffer.restore(self.payloads.drain(..).collect());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn drops_oldest_when_full() {
+        let buffer = ReplayBuffer::new(2);
+        let now = Instant::now();
+
+        buffer.push(json!(1), now);
+        buffer.push(json!(2), now);
+        buffer.push(json!(3), now);
+
+        let payloads: Vec<_> = buffer.take(now).into_iter().map(|(_, p)| p).collect();
+
+        assert_eq!(payloads, vec![json!(2), json!(3)]);
+        assert_eq!(buffer.len(), 0);
+    }
+
+    #[test]
+    fn drops_expired_payloads() {
+        let mut buffer = ReplayBuffer::new(2);
+
+        let now = Instant::now();
+
+        buffer.set_ttl(Duration::from_secs(60));
+        buffer.push(json!(1), now);
+        buffer.push(json!(2), now + Duration::from_secs(30));
+
+        let payloads: Vec<_> = buffer
+            .take(now + Duration::from_secs(61))
+            .into_iter()
+            .map(|(_, p)| p)
+            .collect();
+
+        assert_eq!(payloads, vec![json!(2)]);
+    }
+
+    #[test]
+    fn unconfirmed_payloads_are_restored() {
+        let buffer = ReplayBuffer::new(3);
+        let now = Instant::now();
+
+        buffer.push(json!(1), now);
+        buffer.push(json!(2), now);
+
+        let mut pending = buffer.take_pending(now);
+
+        assert_eq!(pending.front(), Some(&json!(1)));
+        pending.confirm();
+        drop(pending);
+
+        let payloads: Vec<_> = buffer.take(now).into_iter().map(|(_, p)| p).collect();
+
+        assert_eq!(payloads, vec![json!(2)]);
+    }
+
+    #[test]
+    fn can_restore_failed_payloads() {
+        let buffer = ReplayBuffer::new(3);
+        let now = Instant::now();
+
+        buffer.push(json!(1), now);
+        buffer.push(json!(2), now);
+
+        let mut taken = buffer.take(now);
+        let failed = taken.split_off(1);
+
+        buffer.push(json!(3), now);
+        buffer.restore(failed);
+
+        let payloads: Vec<_> = buffer.take(now).into_iter().map(|(_, p)| p).collect();
+
+        assert_eq!(payloads, vec![json!(2), json!(3)]);
+    }
+}
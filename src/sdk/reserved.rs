@@ -0,0 +1,205 @@
+//! Typed setters for the reserved properties OpenPanel gives a conventional meaning
+
+use crate::TrackerResult;
+use crate::sdk::Tracker;
+use reqwest::Response;
+use std::collections::HashMap;
+
+/// Name of the page view events tracked with [Tracker::track_page], as sent by OpenPanel's
+/// web SDK
+pub const PAGE_VIEW_EVENT: &str = "screen_view";
+/// Property name of the page path
+pub const PATH_PROPERTY: &str = "__path";
+/// Property name of the page title
+pub const TITLE_PROPERTY: &str = "__title";
+/// Property name of the referring URL
+pub const REFERRER_PROPERTY: &str = "__referrer";
+/// Property name of the operating system, overriding the one parsed from the user agent
+pub const OS_PROPERTY: &str = "__os";
+/// Property name of the operating system version
+pub const OS_VERSION_PROPERTY: &str = "__osVersion";
+/// Property name of the browser, overriding the one parsed from the user agent
+pub const BROWSER_PROPERTY: &str = "__browser";
+/// Property name of the browser version
+pub const BROWSER_VERSION_PROPERTY: &str = "__browserVersion";
+
+/// Builder of reserved properties with their canonical keys. Properties which aren't set are
+/// omitted.
+///
+/// ```rust
+/// # use openpanel_sdk::sdk::reserved::{PATH_PROPERTY, ReservedProps};
+/// # use std::collections::HashMap;
+/// let properties: HashMap<String, String> = ReservedProps::new()
+///     .with_path("/pricing".to_string())
+///     .with_browser("Firefox".to_string())
+///     .into();
+///
+/// assert_eq!(properties[PATH_PROPERTY], "/pricing");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReservedProps {
+    properties: HashMap<String, String>,
+}
+
+impl ReservedProps {
+    /// Create builder without any property set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with(mut self, key: &str, value: String) -> Self {
+        self.properties.insert(key.to_string(), value);
+        self
+    }
+
+    /// Set [PATH_PROPERTY], e.g. `/pricing`
+    pub fn with_path(self, path: String) -> Self {
+        self.with(PATH_PROPERTY, path)
+    }
+
+    /// Set [TITLE_PROPERTY]
+    pub fn with_title(self, title: String) -> Self {
+        self.with(TITLE_PROPERTY, title)
+    }
+
+    /// Set [REFERRER_PROPERTY], e.g. `https://example.com/blog`
+    pub fn with_referrer(self, referrer: String) -> Self {
+        self.with(REFERRER_PROPERTY, referrer)
+    }
+
+    /// Set [OS_PROPERTY], e.g. `macOS`
+    pub fn with_os(self, os: String) -> Self {
+        self.with(OS_PROPERTY, os)
+    }
+
+    /// Set [OS_VERSION_PROPERTY]
+    pub fn with_os_version(self, version: String) -> Self {
+        self.with(OS_VERSION_PROPERTY, version)
+    }
+
+    /// Set [BROWSER_PROPERTY], e.g. `Firefox`
+    pub fn with_browser(self, browser: String) -> Self {
+        self.with(BROWSER_PROPERTY, browser)
+    }
+
+    /// Set [BROWSER_VERSION_PROPERTY]
+    pub fn with_browser_version(self, version: String) -> Self {
+        self.with(BROWSER_VERSION_PROPERTY, version)
+    }
+}
+
+impl From<ReservedProps> for HashMap<String, String> {
+    fn from(reserved: ReservedProps) -> Self {
+        reserved.properties
+    }
+}
+
+/// Page view tracked with [Tracker::track_page]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageProps {
+    pub profile_id: Option<String>,
+    pub reserved: ReservedProps,
+    pub properties: HashMap<String, String>,
+}
+
+impl PageProps {
+    /// Create page view of the given path
+    pub fn new(path: String) -> Self {
+        Self {
+            profile_id: None,
+            reserved: ReservedProps::new().with_path(path),
+            properties: HashMap::new(),
+        }
+    }
+
+    /// Set the profile id of the page view
+    pub fn with_profile_id(mut self, profile_id: String) -> Self {
+        self.profile_id = Some(profile_id);
+        self
+    }
+
+    /// Set the page title
+    pub fn with_title(mut self, title: String) -> Self {
+        self.reserved = self.reserved.with_title(title);
+        self
+    }
+
+    /// Set the referring URL
+    pub fn with_referrer(mut self, referrer: String) -> Self {
+        self.reserved = self.reserved.with_referrer(referrer);
+        self
+    }
+
+    /// Add further properties. Reserved properties take precedence over properties of the
+    /// same name.
+    pub fn with_properties(mut self, properties: HashMap<String, String>) -> Self {
+        self.properties.extend(properties);
+        self
+    }
+}
+
+impl Tracker {
+    /// Track a page view as [PAGE_VIEW_EVENT] event with the reserved page properties
+    pub async fn track_page(&self, page: PageProps) -> TrackerResult<Response> {
+        let mut properties = page.properties;
+
+        properties.extend(HashMap::from(page.reserved));
+
+        self.track_unfiltered(
+            PAGE_VIEW_EVENT.to_string(),
+            page.profile_id,
+            Some(properties),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    use crate::sdk::testing::MockTransport;
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    use serde_json::json;
+
+    #[test]
+    fn omits_unset_properties() {
+        let properties: HashMap<String, String> = ReservedProps::new()
+            .with_os("macOS".to_string())
+            .with_os_version("14.4".to_string())
+            .into();
+
+        assert_eq!(
+            properties,
+            HashMap::from([
+                (OS_PROPERTY.to_string(), "macOS".to_string()),
+                (OS_VERSION_PROPERTY.to_string(), "14.4".to_string()),
+            ])
+        );
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[tokio::test]
+    async fn can_track_page_views() -> anyhow::Result<()> {
+        let transport = MockTransport::new();
+        let page = PageProps::new("/pricing".to_string())
+            .with_profile_id("rust_123".to_string())
+            .with_title("Pricing".to_string())
+            .with_properties(HashMap::from([
+                ("plan".to_string(), "pro".to_string()),
+                (PATH_PROPERTY.to_string(), "/ignored".to_string()),
+            ]));
+
+        transport.tracker().track_page(page).await?;
+
+        let payload = transport.assert_tracked(PAGE_VIEW_EVENT);
+
+        assert_eq!(payload["payload"]["profileId"], "rust_123");
+        assert_eq!(
+            payload["payload"]["properties"],
+            json!({"__path": "/pricing", "__title": "Pricing", "plan": "pro"})
+        );
+
+        Ok(())
+    }
+}
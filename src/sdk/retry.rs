@@ -0,0 +1,205 @@
+//! Retry of failed requests with exponential backoff and jitter
+
+use crate::TrackerResult;
+use crate::sdk::{Tracker, egress, is_retryable};
+use rand::Rng;
+use reqwest::Response;
+use std::time::Duration;
+
+/// Default number of retries after the first attempt
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+/// Default delay of the first retry
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(100);
+/// Default upper bound of the delay between retries
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Randomization applied to the backoff delay, following
+/// <https://aws.amazon.com/blogs/architecture/exponential-backoff-and-jitter/>.
+/// `exp` below is `min(max_delay, base_delay * 2^attempt)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jitter {
+    /// Wait exactly `exp`
+    None,
+    /// Wait a random delay between zero and `exp`
+    Full,
+    /// Wait `exp / 2` plus a random delay between zero and `exp / 2`
+    Equal,
+    /// Wait a random delay between `base_delay` and three times the previous delay,
+    /// capped at `max_delay`
+    Decorrelated,
+}
+
+/// Retry policy set via [Tracker::with_retry_policy]. Requests failing with a connection
+/// failure, timeout, 429 or 5xx are retried.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: Jitter,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            jitter: Jitter::Full,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Set the number of retries after the first attempt
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the delay of the first retry
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the upper bound of the delay between retries
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set the jitter strategy. Defaults to [Jitter::Full].
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Delay before the given retry, starting at zero. `previous` is the delay before the
+    /// last retry, or the base delay for the first one; it's only used by [Jitter::Decorrelated].
+    pub fn delay(&self, attempt: u32, previous: Duration) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let mut rng = rand::rng();
+
+        match self.jitter {
+            Jitter::None => exp,
+            Jitter::Full => random_between(&mut rng, Duration::ZERO, exp),
+            Jitter::Equal => exp / 2 + random_between(&mut rng, Duration::ZERO, exp / 2),
+            Jitter::Decorrelated => {
+                let upper = previous.saturating_mul(3).max(self.base_delay);
+
+                random_between(&mut rng, self.base_delay, upper).min(self.max_delay)
+            }
+        }
+    }
+}
+
+/// Uniformly distributed delay between `low` and `high`, inclusive
+fn random_between(rng: &mut impl Rng, low: Duration, high: Duration) -> Duration {
+    Duration::from_secs_f64(rng.random_range(low.as_secs_f64()..=high.as_secs_f64()))
+}
+
+impl Tracker {
+    /// Retry failed requests according to the given policy
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Post the payload, retrying retryable failures if a retry policy is set
+    pub(crate) async fn post_with_retry(
+        &self,
+        payload: &serde_json::Value,
+    ) -> TrackerResult<Response> {
+        let Some(policy) = &self.retry else {
+            return self.post(payload).await;
+        };
+
+        let mut delay = policy.base_delay;
+        let mut attempt = 0;
+        let mut retried_bytes = 0;
+
+        loop {
+            let mut result = self.post(payload).await;
+
+            if attempt >= policy.max_retries || !is_retryable(&result) {
+                if let Ok(response) = &mut result {
+                    egress::add_bytes_sent(response, retried_bytes);
+                }
+
+                return result;
+            }
+
+            if let Ok(response) = &result {
+                retried_bytes += egress::bytes_sent(response).unwrap_or_default();
+            }
+
+            delay = policy.delay(attempt, delay);
+            tracing::debug!("Retrying request in {:?}", delay);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(jitter: Jitter) -> RetryPolicy {
+        RetryPolicy::default()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_secs(1))
+            .with_jitter(jitter)
+    }
+
+    #[test]
+    fn delay_without_jitter_is_exponential() {
+        let policy = policy(Jitter::None);
+        let delays = (0..6)
+            .map(|attempt| policy.delay(attempt, Duration::ZERO).as_millis())
+            .collect::<Vec<_>>();
+
+        assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+    }
+
+    #[test]
+    fn delay_with_full_jitter_is_bounded() {
+        let policy = policy(Jitter::Full);
+
+        for _ in 0..100 {
+            assert!(policy.delay(2, Duration::ZERO) <= Duration::from_millis(400));
+            assert!(policy.delay(10, Duration::ZERO) <= Duration::from_secs(1));
+        }
+    }
+
+    #[test]
+    fn delay_with_equal_jitter_is_bounded() {
+        let policy = policy(Jitter::Equal);
+
+        for _ in 0..100 {
+            let delay = policy.delay(2, Duration::ZERO);
+
+            assert!(delay >= Duration::from_millis(200));
+            assert!(delay <= Duration::from_millis(400));
+        }
+    }
+
+    #[test]
+    fn delay_with_decorrelated_jitter_is_bounded() {
+        let policy = policy(Jitter::Decorrelated);
+        let mut previous = Duration::from_millis(100);
+
+        for attempt in 0..100 {
+            let delay = policy.delay(attempt, previous);
+
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= (previous * 3).min(Duration::from_secs(1)));
+
+            previous = delay;
+        }
+    }
+}
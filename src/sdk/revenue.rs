@@ -0,0 +1,219 @@
+//! Typed revenue events with product line items
+
+use crate::sdk::Tracker;
+use crate::sdk::filter::ReasonFilter;
+use crate::{TrackerError, TrackerResult};
+use reqwest::Response;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// Property name of the revenue amount, as expected by OpenPanel
+pub const REVENUE_PROPERTY: &str = "__revenue";
+/// Property name of the revenue currency
+pub const CURRENCY_PROPERTY: &str = "currency";
+/// Property name of the line items
+pub const ITEMS_PROPERTY: &str = "items";
+/// Largest integer OpenPanel's JavaScript backend represents exactly, `2^53 - 1`
+pub const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Encoding of revenue amounts, set via [Tracker::with_amount_encoding]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AmountEncoding {
+    /// Send amounts as JSON numbers, rejecting amounts outside of
+    /// `-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER` with [TrackerError::AmountOutOfRange]
+    #[default]
+    Number,
+    /// Send amounts as decimal strings, keeping every `i64` exact. OpenPanel has to parse them.
+    String,
+}
+
+impl AmountEncoding {
+    /// Encode the amount as JSON value
+    pub fn encode(&self, amount: i64) -> TrackerResult<Value> {
+        match self {
+            AmountEncoding::Number => check_safe_integer(amount).map(|amount| json!(amount)),
+            AmountEncoding::String => Ok(json!(amount.to_string())),
+        }
+    }
+}
+
+/// Check that the amount is within the JS-safe integer range
+pub(crate) fn check_safe_integer(amount: i64) -> TrackerResult<i64> {
+    if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&amount) {
+        Ok(amount)
+    } else {
+        Err(TrackerError::AmountOutOfRange(amount))
+    }
+}
+
+/// Single product of a [RevenueEvent]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LineItem {
+    pub product_id: String,
+    pub quantity: u32,
+    pub unit_price: i64,
+}
+
+impl LineItem {
+    pub fn new(product_id: String, quantity: u32, unit_price: i64) -> Self {
+        Self {
+            product_id,
+            quantity,
+            unit_price,
+        }
+    }
+}
+
+/// Revenue event tracked with [Tracker::track_revenue]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevenueEvent {
+    pub amount: i64,
+    pub currency: String,
+    pub items: Vec<LineItem>,
+}
+
+impl RevenueEvent {
+    /// Create new revenue event without line items
+    pub fn new(amount: i64, currency: String) -> Self {
+        Self {
+            amount,
+            currency,
+            items: Vec::new(),
+        }
+    }
+
+    /// Add a line item to the event
+    pub fn with_item(mut self, item: LineItem) -> Self {
+        self.items.push(item);
+        self
+    }
+}
+
+impl From<RevenueEvent> for HashMap<String, Value> {
+    fn from(revenue: RevenueEvent) -> Self {
+        HashMap::from([
+            (REVENUE_PROPERTY.to_string(), json!(revenue.amount)),
+            (CURRENCY_PROPERTY.to_string(), json!(revenue.currency)),
+            (ITEMS_PROPERTY.to_string(), json!(revenue.items)),
+        ])
+    }
+}
+
+impl Tracker {
+    /// Set how revenue amounts are encoded. Defaults to [AmountEncoding::Number], rejecting
+    /// amounts that would lose precision on OpenPanel's side. Only [Tracker::track_revenue]
+    /// sends the amount in this encoding. [Tracker::revenue] always sends it as string and
+    /// only applies the range check of [AmountEncoding::Number].
+    pub fn with_amount_encoding(mut self, encoding: AmountEncoding) -> Self {
+        self.amount_encoding = encoding;
+        self
+    }
+
+    /// Track revenue with line items. Like [Tracker::revenue], revenue is only sampled if
+    /// [Tracker::sample_all_events] is set.
+    pub async fn track_revenue(
+        &self,
+        profile_id: Option<String>,
+        revenue: RevenueEvent,
+    ) -> TrackerResult<Response> {
+        let profile_id = self.resolve_profile_id(profile_id);
+        let amount = self.amount_encoding.encode(revenue.amount)?;
+        let mut properties = HashMap::from(revenue);
+
+        properties.insert(REVENUE_PROPERTY.to_string(), amount);
+
+        let properties = self.gate_track(
+            "revenue",
+            profile_id.as_deref(),
+            Some(properties),
+            |properties| {
+                let mut properties = properties.unwrap_or_default();
+
+                properties.extend(
+                    self.create_track_properties(None)
+                        .into_iter()
+                        .map(|(key, value)| (key, Value::String(value))),
+                );
+
+                properties
+            },
+            None::<&ReasonFilter<'_>>,
+            self.sample_all.then_some(self.sample_rate),
+        )?;
+        let properties = self.prepare_properties(properties);
+        let payload = self.create_track_payload("revenue", profile_id.as_deref(), &properties);
+
+        self.send_request(payload).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::builder::TrackerBuilder;
+
+    #[test]
+    fn can_convert_revenue_into_properties() {
+        let revenue = RevenueEvent::new(2500, "EUR".to_string())
+            .with_item(LineItem::new("book".to_string(), 2, 1000))
+            .with_item(LineItem::new("pen".to_string(), 1, 500));
+        let properties = HashMap::from(revenue);
+
+        assert_eq!(properties[REVENUE_PROPERTY], json!(2500));
+        assert_eq!(properties[CURRENCY_PROPERTY], json!("EUR"));
+        assert_eq!(
+            properties[ITEMS_PROPERTY],
+            json!([
+                {"product_id": "book", "quantity": 2, "unit_price": 1000},
+                {"product_id": "pen", "quantity": 1, "unit_price": 500},
+            ])
+        );
+    }
+
+    #[test]
+    fn can_encode_amounts() -> anyhow::Result<()> {
+        assert_eq!(
+            AmountEncoding::Number.encode(MAX_SAFE_INTEGER)?,
+            json!(MAX_SAFE_INTEGER)
+        );
+        assert_eq!(
+            AmountEncoding::String.encode(i64::MAX)?,
+            json!(i64::MAX.to_string())
+        );
+        assert!(matches!(
+            AmountEncoding::Number.encode(-MAX_SAFE_INTEGER - 1),
+            Err(TrackerError::AmountOutOfRange(amount)) if amount == -MAX_SAFE_INTEGER - 1
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cannot_track_amount_out_of_range() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default().build()?;
+        let result = tracker
+            .track_revenue(None, RevenueEvent::new(i64::MAX, "EUR".to_string()))
+            .await;
+
+        assert!(matches!(result, Err(TrackerError::AmountOutOfRange(_))));
+
+        let result = tracker.revenue(None, MAX_SAFE_INTEGER + 1, None).await;
+
+        assert!(matches!(result, Err(TrackerError::AmountOutOfRange(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cannot_track_revenue_when_disabled() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default().build()?;
+        let result = tracker
+            .track_revenue(None, RevenueEvent::new(100, "EUR".to_string()))
+            .await;
+
+        assert!(matches!(result, Err(TrackerError::Disabled)));
+
+        Ok(())
+    }
+}
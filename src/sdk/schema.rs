@@ -0,0 +1,121 @@
+//! Allow-list enforcement of property keys
+
+use crate::{TrackerError, TrackerResult};
+use std::collections::{HashMap, HashSet};
+
+/// What happens with property keys not in the allow-list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyPolicy {
+    /// Return [TrackerError::UnknownProperty] and don't send the event
+    Reject,
+    /// Silently drop unknown properties and send the event
+    Strip,
+}
+
+/// Allowed property keys set via [crate::sdk::Tracker::with_allowed_properties]
+#[derive(Debug)]
+pub(crate) struct AllowedProperties {
+    keys: HashSet<String>,
+    policy: PropertyPolicy,
+}
+
+impl AllowedProperties {
+    pub(crate) fn new(keys: HashSet<String>, policy: PropertyPolicy) -> Self {
+        Self { keys, policy }
+    }
+
+    /// Whether the key is allowed. Keys starting with `__` are reserved by OpenPanel,
+    /// e.g. `__revenue`, and always allowed.
+    fn is_allowed(&self, key: &str) -> bool {
+        key.starts_with("__") || self.keys.contains(key)
+    }
+
+    /// Keys not in the allow-list, sorted, if unknown properties are rejected
+    pub(crate) fn rejected_keys<'a, V>(&self, properties: &'a HashMap<String, V>) -> Vec<&'a str> {
+        if self.policy == PropertyPolicy::Strip {
+            return Vec::new();
+        }
+
+        let mut unknown = properties
+            .keys()
+            .map(String::as_str)
+            .filter(|key| !self.is_allowed(key))
+            .collect::<Vec<_>>();
+
+        unknown.sort();
+        unknown
+    }
+
+    /// Apply the policy onto the given properties
+    pub(crate) fn apply<V>(
+        &self,
+        mut properties: HashMap<String, V>,
+    ) -> TrackerResult<HashMap<String, V>> {
+        match self.policy {
+            PropertyPolicy::Reject => match self.rejected_keys(&properties).first() {
+                Some(key) => Err(TrackerError::UnknownProperty(key.to_string())),
+                None => Ok(properties),
+            },
+            PropertyPolicy::Strip => {
+                properties.retain(|key, _| {
+                    let allowed = self.is_allowed(key);
+
+                    if !allowed {
+                        tracing::debug!("Stripping unknown property {}", key);
+                    }
+
+                    allowed
+                });
+
+                Ok(properties)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties() -> HashMap<String, String> {
+        HashMap::from([
+            ("name".to_string(), "rust".to_string()),
+            ("nmae".to_string(), "typo".to_string()),
+        ])
+    }
+
+    fn keys() -> HashSet<String> {
+        HashSet::from(["name".to_string()])
+    }
+
+    #[test]
+    fn can_reject_unknown_properties() {
+        let allowed = AllowedProperties::new(keys(), PropertyPolicy::Reject);
+
+        assert!(matches!(
+            allowed.apply(properties()),
+            Err(TrackerError::UnknownProperty(key)) if key == "nmae"
+        ));
+        assert!(
+            allowed
+                .apply(HashMap::from([
+                    ("name".to_string(), "rust".to_string()),
+                    ("__revenue".to_string(), "100".to_string()),
+                ]))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn can_strip_unknown_properties() -> anyhow::Result<()> {
+        let allowed = AllowedProperties::new(keys(), PropertyPolicy::Strip);
+        let properties = allowed.apply(properties())?;
+
+        assert_eq!(
+            properties,
+            HashMap::from([("name".to_string(), "rust".to_string())])
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+//! Properties scoped to a single logical flow, e.g. a checkout
+
+use crate::TrackerResult;
+use crate::sdk::Tracker;
+use reqwest::Response;
+use std::collections::HashMap;
+
+/// View of a [Tracker] adding scoped properties to its `track` calls, created by
+/// [Tracker::scope]. Properties are layered in this order, later ones overriding earlier ones:
+/// 1. scoped properties
+/// 2. properties given to the call
+/// 3. global properties of the tracker
+#[derive(Debug, Clone)]
+pub struct ScopedTracker<'a> {
+    tracker: &'a Tracker,
+    properties: HashMap<String, String>,
+}
+
+impl<'a> ScopedTracker<'a> {
+    /// Add a scoped property, replacing an existing one of the same key
+    pub fn with_property(mut self, key: String, value: String) -> Self {
+        self.properties.insert(key, value);
+        self
+    }
+
+    /// Scoped properties added to every event
+    pub fn properties(&self) -> &HashMap<String, String> {
+        &self.properties
+    }
+
+    /// Track event on OpenPanel with the scoped properties, see [Tracker::track]
+    pub async fn track(
+        &self,
+        event: String,
+        profile_id: Option<String>,
+        properties: Option<HashMap<String, String>>,
+        filter: Option<&dyn Fn(HashMap<String, String>) -> bool>,
+    ) -> TrackerResult<Response> {
+        let properties = self.create_properties(properties);
+
+        self.tracker
+            .track(event, profile_id, Some(properties), filter)
+            .await
+    }
+
+    /// Add the scoped properties missing from the given ones
+    fn create_properties(
+        &self,
+        properties: Option<HashMap<String, String>>,
+    ) -> HashMap<String, String> {
+        let mut properties = properties.unwrap_or_default();
+
+        for (key, value) in &self.properties {
+            properties
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+
+        properties
+    }
+}
+
+impl Tracker {
+    /// Create a view adding the given properties to its `track` calls, without touching the
+    /// global properties. The view borrows the tracker, so nothing but the properties is cloned.
+    pub fn scope(&self, properties: HashMap<String, String>) -> ScopedTracker<'_> {
+        ScopedTracker {
+            tracker: self,
+            properties,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::testing::MockTransport;
+
+    #[test]
+    fn properties_are_layered() -> anyhow::Result<()> {
+        let tracker = MockTransport::new()
+            .tracker()
+            .with_global_properties(HashMap::from([("env".to_string(), "prod".to_string())]));
+        let scope = tracker
+            .scope(HashMap::from([(
+                "flow".to_string(),
+                "checkout".to_string(),
+            )]))
+            .with_property("env".to_string(), "staging".to_string());
+        let properties = scope.create_properties(Some(HashMap::from([
+            ("flow".to_string(), "signup".to_string()),
+            ("step".to_string(), "payment".to_string()),
+        ])));
+        let properties = tracker.create_track_properties(Some(properties));
+
+        assert_eq!(properties["flow"], "signup");
+        assert_eq!(properties["step"], "payment");
+        assert_eq!(properties["env"], "prod");
+        assert!(tracker.global_property("flow").is_none());
+
+        Ok(())
+    }
+}
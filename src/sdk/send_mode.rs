@@ -0,0 +1,133 @@
+//! Per-event override of the tracker's disabled switch
+
+use crate::sdk::{COMPILED_OUT, Tracker, filter};
+use crate::{TrackerError, TrackerResult};
+use reqwest::Response;
+use std::collections::HashMap;
+
+/// Whether a single event is sent regardless of the tracker's disabled switch, passed to
+/// [Tracker::track_with_mode]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SendMode {
+    /// Send if the tracker is enabled, like [Tracker::track]
+    #[default]
+    Default,
+    /// Send even if the tracker is disabled via `disable` or the enabled flag. Builds with the
+    /// `disabled-at-compile-time` feature still never send.
+    Force,
+    /// Never send, even if the tracker is enabled
+    Skip,
+}
+
+impl SendMode {
+    /// Whether the event is sent, given whether the tracker is enabled
+    pub fn is_enabled(&self, tracker_enabled: bool) -> bool {
+        match self {
+            SendMode::Default => tracker_enabled,
+            SendMode::Force => !COMPILED_OUT,
+            SendMode::Skip => false,
+        }
+    }
+}
+
+impl Tracker {
+    /// Track event like [Tracker::track], overriding the disabled switch for this event.
+    /// [SendMode::Skip] returns [TrackerError::Disabled] right away, before filters and
+    /// sampling. Otherwise the rollout, filters registered via [Tracker::add_filter] and
+    /// sampling apply as usual, so a forced event can still be filtered or sampled out.
+    /// Forced events don't wait for earlier events of ordered delivery.
+    pub async fn track_with_mode(
+        &self,
+        event: String,
+        profile_id: Option<String>,
+        properties: Option<HashMap<String, String>>,
+        mode: SendMode,
+    ) -> TrackerResult<Response> {
+        match mode {
+            SendMode::Skip => Err(TrackerError::Disabled),
+            SendMode::Force => {
+                let profile_id = self.resolve_profile_id(profile_id);
+                let payload = self.build_track_payload::<filter::ReasonFilter<'_>>(
+                    &event, profile_id, properties, None, None,
+                )?;
+
+                self.send_request_with_mode(payload, mode).await
+            }
+            SendMode::Default => self.track_unfiltered(event, profile_id, properties).await,
+        }
+    }
+
+    /// Track event like [Tracker::track], even if the tracker is disabled, e.g. a licensing
+    /// ping in an otherwise disabled build. See [Tracker::track_with_mode].
+    pub async fn track_forced(
+        &self,
+        event: String,
+        profile_id: Option<String>,
+        properties: Option<HashMap<String, String>>,
+    ) -> TrackerResult<Response> {
+        self.track_with_mode(event, profile_id, properties, SendMode::Force)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::testing::MockTransport;
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[tokio::test]
+    async fn can_force_events_of_disabled_tracker() -> anyhow::Result<()> {
+        let transport = MockTransport::new();
+        let tracker = transport.tracker().disable();
+
+        assert!(matches!(
+            tracker.track("ignored".to_string(), None, None, None).await,
+            Err(TrackerError::Disabled)
+        ));
+        assert_eq!(
+            tracker
+                .track_forced("license_ping".to_string(), None, None)
+                .await?
+                .status(),
+            200
+        );
+        assert_eq!(transport.events(), vec!["license_ping"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn forced_events_are_still_filtered_and_sampled() {
+        let transport = MockTransport::new();
+        let tracker = transport
+            .tracker()
+            .disable()
+            .add_filter("all".to_string(), |_, _| true);
+
+        assert!(matches!(
+            tracker.track_forced("test".to_string(), None, None).await,
+            Err(TrackerError::Filtered { .. })
+        ));
+
+        let tracker = transport.tracker().disable().with_sample_rate(0.0);
+
+        assert!(matches!(
+            tracker.track_forced("test".to_string(), None, None).await,
+            Err(TrackerError::Sampled)
+        ));
+        assert!(transport.events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn can_skip_events_of_enabled_tracker() {
+        let transport = MockTransport::new();
+        let result = transport
+            .tracker()
+            .track_with_mode("test".to_string(), None, None, SendMode::Skip)
+            .await;
+
+        assert!(matches!(result, Err(TrackerError::Disabled)));
+        assert!(transport.payloads().is_empty());
+    }
+}
@@ -0,0 +1,740 @@
+//! Channel backed sender for high throughput event ingestion
+
+use crate::sdk::Tracker;
+#[cfg(feature = "compression")]
+use crate::sdk::budget::{CompressedBuffer, EncodedEvent, MEMORY_BUDGET_REASON, OverflowPolicy};
+#[cfg(feature = "tracing-layer")]
+use crate::sdk::clock::Clock;
+use crate::{TrackerError, TrackerResult};
+use reqwest::Response;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+#[cfg(feature = "tracing-layer")]
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::{Stream, StreamExt};
+
+/// Default capacity of the event channel
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 1024;
+/// Default number of events collected before a batch is flushed
+pub const DEFAULT_BATCH_SIZE: usize = 50;
+/// Default interval after which a partial batch is flushed
+pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+/// Default number of delivery results kept for lagging result streams
+pub const DEFAULT_RESULTS_CAPACITY: usize = 1024;
+
+/// Priority of an event sent through an [EventSender]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Sent once the batch is full or flushed explicitly, e.g. scroll events. Partial batches
+    /// of only low priority events aren't flushed on the flush interval.
+    Low,
+    /// Sent once the batch is full or the flush interval has passed
+    #[default]
+    Normal,
+    /// Sent right away, bypassing the batch, e.g. purchases
+    High,
+}
+
+/// Event sent through an [EventSender]
+#[derive(Debug, Clone)]
+pub struct TrackEvent {
+    pub name: String,
+    pub profile_id: Option<String>,
+    pub properties: Option<HashMap<String, String>>,
+    pub priority: Priority,
+}
+
+impl TrackEvent {
+    /// Create new event with the given name
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            profile_id: None,
+            properties: None,
+            priority: Priority::default(),
+        }
+    }
+
+    /// Set the priority of the event. Defaults to [Priority::Normal].
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the profile id of the event
+    pub fn with_profile_id(mut self, profile_id: String) -> Self {
+        self.profile_id = Some(profile_id);
+        self
+    }
+
+    /// Set the properties of the event
+    pub fn with_properties(mut self, properties: HashMap<String, String>) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+}
+
+/// Outcome of sending an event from the background task
+#[derive(Debug, Clone)]
+pub struct DeliveryResult {
+    /// Sequence number of the event, assigned in the order events were received
+    pub id: u64,
+    /// Name of the event
+    pub event: String,
+    /// HTTP status returned by OpenPanel, if a response was received
+    pub status: Option<u16>,
+    /// Error message, if the event couldn't be sent
+    pub error: Option<String>,
+}
+
+impl DeliveryResult {
+    /// Whether the event was accepted by OpenPanel
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+            && self
+                .status
+                .is_some_and(|status| (200..300).contains(&status))
+    }
+}
+
+/// Counts of events handled by the background task since the last report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlushReport {
+    /// Events accepted by OpenPanel
+    pub sent: usize,
+    /// Events which couldn't be sent or were rejected by OpenPanel
+    pub failed: usize,
+    /// Events intentionally not sent, e.g. filtered, sampled out or disabled
+    pub dropped: usize,
+}
+
+/// Message sent to the background task
+#[derive(Debug)]
+enum Message {
+    Event(TrackEvent),
+    Flush(oneshot::Sender<FlushReport>),
+}
+
+/// Configuration of the background task started by [Tracker::into_sender_with_config]
+#[derive(Debug, Clone)]
+pub struct SenderConfig {
+    capacity: usize,
+    batch_size: usize,
+    flush_interval: Duration,
+    results_capacity: usize,
+    #[cfg(feature = "compression")]
+    memory_budget: Option<usize>,
+    #[cfg(feature = "compression")]
+    overflow_policy: OverflowPolicy,
+}
+
+impl Default for SenderConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_CHANNEL_CAPACITY,
+            batch_size: DEFAULT_BATCH_SIZE,
+            flush_interval: DEFAULT_FLUSH_INTERVAL,
+            results_capacity: DEFAULT_RESULTS_CAPACITY,
+            #[cfg(feature = "compression")]
+            memory_budget: None,
+            #[cfg(feature = "compression")]
+            overflow_policy: OverflowPolicy::default(),
+        }
+    }
+}
+
+impl SenderConfig {
+    /// Set the capacity of the channel. Once the channel is full, [EventSender::send] waits
+    /// until the background task has made room
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Set the number of events collected before a batch is flushed
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set the interval after which a partial batch is flushed
+    pub fn with_flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    /// Set the number of delivery results buffered per results stream. If a consumer lags
+    /// behind, the oldest results are dropped.
+    pub fn with_results_capacity(mut self, results_capacity: usize) -> Self {
+        self.results_capacity = results_capacity.max(1);
+        self
+    }
+
+    /// Cap the buffered batch by its size in bytes instead of the batch size, keeping the
+    /// events gzip-compressed until the batch is flushed. Once an event doesn't fit, the
+    /// [OverflowPolicy] set via [SenderConfig::with_overflow_policy] applies.
+    #[cfg(feature = "compression")]
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes);
+        self
+    }
+
+    /// Set what happens to events exceeding the memory budget. Defaults to
+    /// [OverflowPolicy::Flush].
+    #[cfg(feature = "compression")]
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+}
+
+/// Cloneable handle used to send events to the background task.
+/// The task flushes all remaining events and completes once every handle is dropped.
+#[derive(Debug, Clone)]
+pub struct EventSender {
+    tx: mpsc::Sender<Message>,
+    results: broadcast::WeakSender<DeliveryResult>,
+    /// Clock of the tracker, measuring the span durations of the tracing layer
+    #[cfg(feature = "tracing-layer")]
+    pub(crate) clock: Arc<dyn Clock>,
+}
+
+impl EventSender {
+    /// Send event to the background task. Waits if the channel is full.
+    pub async fn send(&self, event: TrackEvent) -> TrackerResult<()> {
+        self.tx
+            .send(Message::Event(event))
+            .await
+            .map_err(|_| TrackerError::SenderClosed)
+    }
+
+    /// Enqueue event without waiting. Returns [TrackerError::QueueFull] if the channel is full.
+    pub fn try_send(&self, event: TrackEvent) -> TrackerResult<()> {
+        self.tx
+            .try_send(Message::Event(event))
+            .map_err(|e| match e {
+                mpsc::error::TrySendError::Full(_) => TrackerError::QueueFull,
+                mpsc::error::TrySendError::Closed(_) => TrackerError::SenderClosed,
+            })
+    }
+
+    /// Send all events queued before this call and return the counts of events handled since
+    /// the last report. Use this e.g. at the end of a serverless invocation, before the runtime
+    /// is frozen.
+    pub async fn flush_with_report(&self) -> TrackerResult<FlushReport> {
+        let (tx, rx) = oneshot::channel();
+
+        self.tx
+            .send(Message::Flush(tx))
+            .await
+            .map_err(|_| TrackerError::SenderClosed)?;
+
+        rx.await.map_err(|_| TrackerError::SenderClosed)
+    }
+
+    /// Stream of delivery results for events sent after subscribing.
+    /// The stream is bounded by `results_capacity`; if the consumer lags behind, the oldest
+    /// results are dropped with a warning. The stream ends once the background task completes.
+    pub fn results_stream(&self) -> impl Stream<Item = DeliveryResult> + use<> {
+        let rx = match self.results.upgrade() {
+            Some(results) => results.subscribe(),
+            None => broadcast::channel(1).1,
+        };
+
+        BroadcastStream::new(rx).filter_map(|result| match result {
+            Ok(result) => Some(result),
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                tracing::warn!(
+                    "Results stream lagging, dropped {} delivery results",
+                    skipped
+                );
+                None
+            }
+        })
+    }
+}
+
+impl Tracker {
+    /// Move the tracker into a background task consuming events from a channel.
+    /// Uses the default [SenderConfig]
+    pub fn into_sender(self) -> (EventSender, JoinHandle<()>) {
+        self.into_sender_with_config(SenderConfig::default())
+    }
+
+    /// Move the tracker into a background task consuming events from a channel.
+    /// Events are collected into batches, which are sent once `batch_size` events are
+    /// buffered or `flush_interval` has passed, ordered by priority, then age. See [Priority]
+    /// for how priorities change this.
+    pub fn into_sender_with_config(self, config: SenderConfig) -> (EventSender, JoinHandle<()>) {
+        let (tx, rx) = mpsc::channel(config.capacity);
+        let (results, _) = broadcast::channel(config.results_capacity);
+        let sender = EventSender {
+            tx,
+            results: results.downgrade(),
+            #[cfg(feature = "tracing-layer")]
+            clock: Arc::clone(&self.clock),
+        };
+        let runtime = self.runtime_handle();
+        let handle = runtime.spawn(run(self, rx, results, config));
+
+        (sender, handle)
+    }
+
+    /// Track the events of a stream with at most `concurrency` requests in flight, e.g. to drain
+    /// an async source without overwhelming OpenPanel. Results are yielded in the order of the
+    /// events; the events are sent lazily while the returned stream is polled.
+    pub fn track_stream<'a, S>(
+        &'a self,
+        events: S,
+        concurrency: usize,
+    ) -> impl Stream<Item = TrackerResult<Response>> + 'a
+    where
+        S: Stream<Item = TrackEvent> + 'a,
+    {
+        futures_util::StreamExt::buffered(
+            events.map(move |event| {
+                self.track_unfiltered(event.name, event.profile_id, event.properties)
+            }),
+            concurrency.max(1),
+        )
+    }
+}
+
+/// State of the background task
+struct Worker {
+    tracker: Tracker,
+    batch: Vec<(u64, TrackEvent)>,
+    next_id: u64,
+    results: broadcast::Sender<DeliveryResult>,
+    report: FlushReport,
+    #[cfg(feature = "compression")]
+    compressed: Option<CompressedBuffer>,
+}
+
+/// Drain the channel, batching events by size and time
+async fn run(
+    tracker: Tracker,
+    mut rx: mpsc::Receiver<Message>,
+    results: broadcast::Sender<DeliveryResult>,
+    config: SenderConfig,
+) {
+    let mut worker = Worker {
+        tracker,
+        batch: Vec::with_capacity(config.batch_size),
+        next_id: 0,
+        results,
+        report: FlushReport::default(),
+        #[cfg(feature = "compression")]
+        compressed: config
+            .memory_budget
+            .map(|budget| CompressedBuffer::new(budget, config.overflow_policy)),
+    };
+    let mut interval = tokio::time::interval(config.flush_interval);
+
+    // first tick completes immediately
+    interval.tick().await;
+
+    loop {
+        tokio::select! {
+            message = rx.recv() => match message {
+                Some(Message::Event(event)) => {
+                    let id = worker.next_id;
+
+                    worker.next_id += 1;
+
+                    if event.priority == Priority::High {
+                        worker.send(id, event).await;
+                        continue;
+                    }
+
+                    if worker.buffer(id, event, config.batch_size).await {
+                        interval.reset();
+                    }
+                }
+                Some(Message::Flush(reply)) => {
+                    worker.flush().await;
+                    interval.reset();
+
+                    // the caller may have stopped waiting for the report
+                    let _ = reply.send(std::mem::take(&mut worker.report));
+                }
+                None => break,
+            },
+            _ = interval.tick() => {
+                if worker.is_ready() {
+                    worker.flush().await;
+                }
+            }
+        }
+    }
+
+    worker.flush().await;
+}
+
+impl Worker {
+    /// Add the event to the batch, flushing the batch once it's full. Returns whether the
+    /// batch was flushed.
+    async fn buffer(&mut self, id: u64, event: TrackEvent, batch_size: usize) -> bool {
+        #[cfg(feature = "compression")]
+        if self.compressed.is_some() {
+            return self.buffer_compressed(id, event).await;
+        }
+
+        self.batch.push((id, event));
+
+        if self.batch.len() >= batch_size {
+            self.flush().await;
+            return true;
+        }
+
+        false
+    }
+
+    /// Add the event to the compressed buffer, applying the overflow policy if it doesn't fit
+    #[cfg(feature = "compression")]
+    async fn buffer_compressed(&mut self, id: u64, event: TrackEvent) -> bool {
+        let Some(buffer) = self.compressed.as_mut() else {
+            return false;
+        };
+        let encoded = match EncodedEvent::new(id, &event) {
+            Ok(encoded) => encoded,
+            Err(e) => {
+                self.record(id, event.name, Err(e));
+                return false;
+            }
+        };
+
+        if buffer.fits(&encoded) {
+            if let Err(e) = buffer.push(encoded) {
+                self.record(id, event.name, Err(e));
+            }
+
+            return false;
+        }
+
+        match buffer.policy {
+            OverflowPolicy::DropNewest => {
+                let reason = MEMORY_BUDGET_REASON.to_string();
+
+                self.record(id, event.name, Err(TrackerError::Filtered { reason }));
+                false
+            }
+            OverflowPolicy::Flush => {
+                self.flush().await;
+
+                let buffer = self.compressed.as_mut().expect("compressed buffer");
+
+                // an event larger than the whole budget is sent right away
+                if buffer.fits(&encoded) {
+                    if let Err(e) = buffer.push(encoded) {
+                        self.record(id, event.name, Err(e));
+                    }
+                } else {
+                    self.send(id, event).await;
+                }
+
+                true
+            }
+        }
+    }
+
+    /// Whether a partial batch is flushed on the flush interval, see [Priority::Low]
+    fn is_ready(&self) -> bool {
+        #[cfg(feature = "compression")]
+        if let Some(buffer) = &self.compressed {
+            return buffer.is_ready();
+        }
+
+        self.batch
+            .iter()
+            .any(|(_, event)| event.priority > Priority::Low)
+    }
+
+    /// Send all buffered events. OpenPanel accepts one event per request, so a batch is sent
+    /// as consecutive requests.
+    async fn flush(&mut self) {
+        #[cfg(feature = "compression")]
+        if let Some(buffer) = self.compressed.as_mut() {
+            let len = buffer.len();
+
+            match buffer.take() {
+                Ok(events) => self.batch.extend(events),
+                Err(e) => {
+                    tracing::warn!("Error restoring {} buffered events: {}", len, e);
+                    self.report.failed += len;
+                }
+            }
+        }
+
+        let mut batch = std::mem::take(&mut self.batch);
+
+        batch.sort_by_key(|(id, event)| (Reverse(event.priority), *id));
+
+        for (id, event) in batch.drain(..) {
+            self.send(id, event).await;
+        }
+
+        // keep the allocation for the next batch
+        self.batch = batch;
+    }
+
+    /// Send a single event and publish its delivery result
+    async fn send(&mut self, id: u64, event: TrackEvent) {
+        let name = event.name.clone();
+        let result = self
+            .tracker
+            .track_unfiltered(event.name, event.profile_id, event.properties)
+            .await;
+
+        self.record(id, name, result);
+    }
+
+    /// Count the outcome of an event and publish its delivery result
+    fn record(&mut self, id: u64, name: String, result: TrackerResult<Response>) {
+        let result = match result {
+            Ok(response) => {
+                let result = DeliveryResult {
+                    id,
+                    event: name,
+                    status: Some(response.status().as_u16()),
+                    error: None,
+                };
+
+                if result.is_success() {
+                    self.report.sent += 1;
+                } else {
+                    self.report.failed += 1;
+                }
+
+                result
+            }
+            Err(e) => {
+                if e.is_intentional_skip() {
+                    self.report.dropped += 1;
+                } else {
+                    tracing::warn!("Error sending event: {}", e);
+                    self.report.failed += 1;
+                }
+
+                DeliveryResult {
+                    id,
+                    event: name,
+                    status: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+
+        // no subscribers is not an error
+        let _ = self.results.send(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::builder::TrackerBuilder;
+
+    #[tokio::test]
+    async fn can_track_stream() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default().build()?;
+        let events = tokio_stream::iter((0..10).map(|i| TrackEvent::new(format!("event_{}", i))));
+        let results = tracker.track_stream(events, 3).collect::<Vec<_>>().await;
+
+        assert_eq!(results.len(), 10);
+        assert!(
+            results
+                .iter()
+                .all(|result| matches!(result, Err(TrackerError::Disabled)))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sender_completes_after_close() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default().build()?;
+        let (sender, handle) = tracker.into_sender();
+
+        sender
+            .send(TrackEvent::new("test_event".to_string()))
+            .await?;
+        drop(sender);
+
+        handle.await?;
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[test]
+    fn sender_runs_on_given_runtime() -> anyhow::Result<()> {
+        let runtime = tokio::runtime::Runtime::new()?;
+        let transport = crate::sdk::testing::MockTransport::new();
+        let tracker = transport
+            .tracker()
+            .with_runtime_handle(runtime.handle().clone());
+        // no runtime is entered here, so spawning on the current one would panic
+        let (sender, handle) = tracker.into_sender();
+
+        sender.try_send(TrackEvent::new("test_event".to_string()))?;
+        drop(sender);
+        runtime.block_on(handle)?;
+
+        assert_eq!(transport.events(), vec!["test_event"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn can_stream_delivery_results() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default().build()?;
+        let (sender, handle) = tracker.into_sender();
+        let results = sender.results_stream();
+
+        sender.send(TrackEvent::new("first".to_string())).await?;
+        sender.send(TrackEvent::new("second".to_string())).await?;
+        drop(sender);
+        handle.await?;
+
+        let results: Vec<DeliveryResult> = results.collect().await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, 0);
+        assert_eq!(results[0].event, "first");
+        assert_eq!(results[1].id, 1);
+        assert!(results.iter().all(|result| !result.is_success()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn events_are_sent_by_priority() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default().build()?;
+        let config = SenderConfig::default().with_flush_interval(Duration::from_millis(10));
+        let (sender, handle) = tracker.into_sender_with_config(config);
+        let mut results = sender.results_stream();
+        let event =
+            |name: &str, priority| TrackEvent::new(name.to_string()).with_priority(priority);
+
+        sender.send(event("scroll", Priority::Low)).await?;
+        sender.send(event("purchase", Priority::High)).await?;
+
+        assert_eq!(
+            results.next().await.map(|result| result.event),
+            Some("purchase".to_string())
+        );
+
+        // a partial batch of low priority events waits past the flush interval
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        sender.send(event("page_view", Priority::Normal)).await?;
+        drop(sender);
+        handle.await?;
+
+        let events = results.map(|result| result.event).collect::<Vec<_>>().await;
+
+        assert_eq!(events, vec!["page_view", "scroll"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn can_flush_with_report() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default().build()?;
+        let (sender, _handle) = tracker.into_sender();
+
+        sender.send(TrackEvent::new("first".to_string())).await?;
+        sender.send(TrackEvent::new("second".to_string())).await?;
+
+        let report = sender.flush_with_report().await?;
+
+        assert_eq!(
+            report,
+            FlushReport {
+                sent: 0,
+                failed: 0,
+                dropped: 2
+            }
+        );
+        assert_eq!(sender.flush_with_report().await?, FlushReport::default());
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "compression", not(feature = "disabled-at-compile-time")))]
+    async fn send_within_budget(
+        policy: OverflowPolicy,
+    ) -> anyhow::Result<(FlushReport, Vec<String>)> {
+        let transport = crate::sdk::testing::MockTransport::new();
+        let config = SenderConfig::default()
+            .with_memory_budget(256)
+            .with_overflow_policy(policy);
+        let (sender, _handle) = transport.tracker().into_sender_with_config(config);
+
+        for i in 0..20 {
+            let properties = HashMap::from([("path".to_string(), format!("/articles/{}", i))]);
+
+            sender
+                .send(TrackEvent::new(format!("event_{}", i)).with_properties(properties))
+                .await?;
+        }
+
+        let report = sender.flush_with_report().await?;
+
+        Ok((report, transport.events()))
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn flushes_batch_exceeding_memory_budget() -> anyhow::Result<()> {
+        let (report, events) = send_within_budget(OverflowPolicy::Flush).await?;
+
+        assert_eq!(report.sent, 20);
+        assert_eq!(
+            events,
+            (0..20).map(|i| format!("event_{}", i)).collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn drops_events_exceeding_memory_budget() -> anyhow::Result<()> {
+        let (report, events) = send_within_budget(OverflowPolicy::DropNewest).await?;
+
+        assert!(report.sent > 0 && report.sent < 20);
+        assert_eq!(report.dropped, 20 - report.sent);
+        assert_eq!(report.failed, 0);
+        assert_eq!(events.len(), report.sent);
+        assert_eq!(events[0], "event_0");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cannot_send_after_task_finished() -> anyhow::Result<()> {
+        let tracker = TrackerBuilder::default().build()?;
+        let (sender, handle) = tracker.into_sender();
+
+        handle.abort();
+        let _ = handle.await;
+
+        let result = sender.send(TrackEvent::new("test_event".to_string())).await;
+
+        assert!(matches!(result, Err(TrackerError::SenderClosed)));
+
+        let result = sender.try_send(TrackEvent::new("test_event".to_string()));
+
+        assert!(matches!(result, Err(TrackerError::SenderClosed)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+//! Pluggable serialization of payloads into request bodies
+
+use crate::TrackerError;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Serialization of payloads into request bodies, set via
+/// [crate::sdk::Tracker::with_serializer]. Implement this to send another wire format, e.g.
+/// to a collector accepting MessagePack, and set the matching `Content-Type` header via
+/// [crate::sdk::Tracker::with_header]. Defaults to [JsonSerializer].
+pub trait PayloadSerializer: Send + Sync {
+    /// Serialize the payload into the request body
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, TrackerError>;
+}
+
+/// Serializes payloads as compact JSON, as expected by OpenPanel. Object keys are sorted, so
+/// the same payload always serializes to the same bytes, e.g. for snapshot tests.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonSerializer;
+
+impl PayloadSerializer for JsonSerializer {
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, TrackerError> {
+        Ok(serde_json::to_vec(&SortedKeys(value))?)
+    }
+}
+
+/// Value serializing objects with sorted keys. Without this, the key order depends on the
+/// insertion order if another crate enables serde_json's `preserve_order` feature, and so on
+/// the iteration order of the `HashMap` the properties came from.
+pub(crate) struct SortedKeys<'a>(pub(crate) &'a Value);
+
+impl Serialize for SortedKeys<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Object(object) => object
+                .iter()
+                .map(|(key, value)| (key, SortedKeys(value)))
+                .collect::<BTreeMap<_, _>>()
+                .serialize(serializer),
+            Value::Array(values) => serializer.collect_seq(values.iter().map(SortedKeys)),
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn json_serializer_writes_compact_json() {
+        let body = JsonSerializer
+            .serialize(&json!({"type": "track", "payload": {"name": "test"}}))
+            .unwrap();
+
+        assert_eq!(body, br#"{"payload":{"name":"test"},"type":"track"}"#);
+    }
+
+    #[test]
+    fn serializes_same_properties_to_same_bytes() {
+        let keys = (0..32).map(|i| format!("key_{:02}", i)).collect::<Vec<_>>();
+        // every map has its own random hasher, so their iteration orders differ like across runs
+        let forward = keys
+            .iter()
+            .map(|key| (key.clone(), key.clone()))
+            .collect::<HashMap<_, _>>();
+        let backward = keys
+            .iter()
+            .rev()
+            .map(|key| (key.clone(), key.clone()))
+            .collect::<HashMap<_, _>>();
+        let serialize = |properties: &HashMap<String, String>| {
+            let payload = crate::sdk::payload::track("test", None, properties);
+
+            String::from_utf8(JsonSerializer.serialize(&payload).unwrap()).unwrap()
+        };
+        let body = serialize(&forward);
+
+        assert_eq!(serialize(&backward), body);
+
+        let positions = keys
+            .iter()
+            .map(|key| body.find(&format!(r#""{}":"#, key)).unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(positions.is_sorted());
+    }
+
+    #[test]
+    fn sorts_nested_keys() {
+        let value = json!({"b": [{"d": 1, "c": 2}], "a": {"f": null, "e": true}});
+
+        assert_eq!(
+            serde_json::to_string(&SortedKeys(&value)).unwrap(),
+            r#"{"a":{"e":true,"f":null},"b":[{"c":2,"d":1}]}"#
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Handling of explicit event timestamps deviating from the tracker's clock
+
+use serde_json::Value;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Property name of an explicit event timestamp, as expected by OpenPanel
+pub const TIMESTAMP_PROPERTY: &str = "__timestamp";
+
+/// Handling of [TIMESTAMP_PROPERTY] values deviating from the tracker's clock by more than the
+/// given threshold, set via [crate::sdk::Tracker::with_clock_skew_policy]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSkewPolicy {
+    /// Remove the timestamp, so OpenPanel uses the time it received the event
+    Drop(Duration),
+    /// Move the timestamp to the nearest time within the threshold
+    Clamp(Duration),
+}
+
+impl ClockSkewPolicy {
+    fn threshold(&self) -> Duration {
+        match self {
+            ClockSkewPolicy::Drop(threshold) | ClockSkewPolicy::Clamp(threshold) => *threshold,
+        }
+    }
+
+    /// Check the timestamp of a `track` payload against `now`. Timestamps which aren't
+    /// RFC 3339 strings are left unchanged.
+    pub fn apply(&self, mut payload: Value, now: SystemTime) -> Value {
+        let Some(properties) = payload
+            .pointer_mut("/payload/properties")
+            .and_then(Value::as_object_mut)
+        else {
+            return payload;
+        };
+        let Some(timestamp) = properties
+            .get(TIMESTAMP_PROPERTY)
+            .and_then(Value::as_str)
+            .and_then(parse_rfc3339)
+        else {
+            return payload;
+        };
+
+        let threshold = self.threshold();
+        let (skew, clamped) = match timestamp.duration_since(now) {
+            Ok(ahead) => (ahead, now.checked_add(threshold)),
+            Err(behind) => (behind.duration(), now.checked_sub(threshold)),
+        };
+
+        if skew <= threshold {
+            return payload;
+        }
+
+        tracing::warn!(
+            "Event timestamp deviates from the tracker clock by {:?}",
+            skew
+        );
+
+        match (self, clamped) {
+            (ClockSkewPolicy::Clamp(_), Some(clamped)) => {
+                properties.insert(
+                    TIMESTAMP_PROPERTY.to_string(),
+                    Value::String(format_rfc3339(clamped)),
+                );
+            }
+            _ => {
+                properties.remove(TIMESTAMP_PROPERTY);
+            }
+        }
+
+        payload
+    }
+}
+
+/// Parse an RFC 3339 timestamp like `2026-01-01T00:00:00.000Z` or `2026-01-01T02:00:00+02:00`
+pub(crate) fn parse_rfc3339(value: &str) -> Option<SystemTime> {
+    let bytes = value.as_bytes();
+    let number = |range| digits(value, range);
+
+    if bytes.len() < 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !matches!(bytes[10], b'T' | b't' | b' ')
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return None;
+    }
+
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+
+    let mut rest = &value[19..];
+    let mut nanos = 0u32;
+
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = fraction.bytes().take_while(u8::is_ascii_digit).count();
+
+        if len == 0 {
+            return None;
+        }
+
+        let digits = &fraction[..len.min(9)];
+
+        nanos = digits.parse::<u32>().ok()? * 10u32.pow(9 - digits.len() as u32);
+        rest = &fraction[len..];
+    }
+
+    let offset = match rest.as_bytes() {
+        [b'Z' | b'z'] => 0,
+        [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+            let offset = digits(rest, 1..3)? * 3600 + digits(rest, 4..6)? * 60;
+
+            if *sign == b'-' { -offset } else { offset }
+        }
+        _ => return None,
+    };
+
+    let seconds =
+        days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset;
+    let since_epoch = Duration::new(seconds.unsigned_abs(), 0);
+
+    if seconds >= 0 {
+        UNIX_EPOCH.checked_add(since_epoch)
+    } else {
+        UNIX_EPOCH.checked_sub(since_epoch)
+    }
+    .and_then(|time| time.checked_add(Duration::from_nanos(nanos as u64)))
+}
+
+/// Parse the ASCII digits in the given range
+fn digits(value: &str, range: std::ops::Range<usize>) -> Option<i64> {
+    let digits = value.get(range)?;
+
+    if digits.bytes().all(|b| b.is_ascii_digit()) {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Format a time as RFC 3339 UTC timestamp with millisecond precision
+pub(crate) fn format_rfc3339(time: SystemTime) -> String {
+    let (seconds, millis) = match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => (since.as_secs() as i64, since.subsec_millis()),
+        Err(before) => {
+            let before = before.duration();
+            let carry = (before.subsec_nanos() > 0) as i64;
+
+            (
+                -(before.as_secs() as i64) - carry,
+                (1000 - before.subsec_millis()) % 1000,
+            )
+        }
+    };
+    let (days, seconds) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+        millis
+    )
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Proleptic Gregorian date of the given days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+
+    (year_of_era + era * 400 + (month <= 2) as i64, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::payload;
+    use serde_json::json;
+
+    fn at(timestamp: &str) -> SystemTime {
+        parse_rfc3339(timestamp).unwrap()
+    }
+
+    #[test]
+    fn can_parse_and_format_timestamps() {
+        assert_eq!(
+            at("2026-01-01T00:00:00Z"),
+            UNIX_EPOCH + Duration::from_secs(1_767_225_600)
+        );
+        assert_eq!(at("2026-01-01T02:00:00+02:00"), at("2026-01-01T00:00:00Z"));
+        assert_eq!(
+            format_rfc3339(at("2024-02-29T23:59:59.250Z")),
+            "2024-02-29T23:59:59.250Z"
+        );
+        assert_eq!(
+            format_rfc3339(at("1969-12-31T23:59:59.5Z")),
+            "1969-12-31T23:59:59.500Z"
+        );
+        assert!(parse_rfc3339("2026-01-01").is_none());
+        assert!(parse_rfc3339("2026-13-01T00:00:00Z").is_none());
+    }
+
+    #[test]
+    fn can_handle_skewed_timestamps() {
+        let now = at("2026-01-01T00:00:00Z");
+        let event = |timestamp: &str| {
+            payload::track("test", None, &json!({ TIMESTAMP_PROPERTY: timestamp }))
+        };
+        let hour = Duration::from_secs(3600);
+
+        let clamped = ClockSkewPolicy::Clamp(hour).apply(event("2030-01-01T00:00:00Z"), now);
+
+        assert_eq!(
+            clamped["payload"]["properties"][TIMESTAMP_PROPERTY],
+            "2026-01-01T01:00:00.000Z"
+        );
+
+        let dropped = ClockSkewPolicy::Drop(hour).apply(event("2000-01-01T00:00:00Z"), now);
+
+        assert!(
+            dropped["payload"]["properties"]
+                .get(TIMESTAMP_PROPERTY)
+                .is_none()
+        );
+
+        let within = event("2026-01-01T00:30:00Z");
+
+        assert_eq!(
+            ClockSkewPolicy::Drop(hour).apply(within.clone(), now),
+            within
+        );
+
+        let invalid = event("yesterday");
+
+        assert_eq!(
+            ClockSkewPolicy::Drop(hour).apply(invalid.clone(), now),
+            invalid
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! Rolling summary of recent request latencies
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Number of recent requests the summary returned by [crate::sdk::Tracker::stats] is based on
+pub const STATS_WINDOW: usize = 256;
+
+/// Approximate summary of the most recent [STATS_WINDOW] requests
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrackerStats {
+    /// Number of requests in the window
+    pub count: usize,
+    /// Median latency, `None` without requests
+    pub p50: Option<Duration>,
+    /// 95th percentile latency, `None` without requests
+    pub p95: Option<Duration>,
+    /// Share (0.0 - 1.0) of requests which failed or returned a non-2xx status
+    pub error_rate: f64,
+}
+
+/// Bounded ring buffer of request latencies and outcomes
+#[derive(Debug)]
+pub(crate) struct LatencyWindow {
+    samples: Mutex<VecDeque<(Duration, bool)>>,
+}
+
+impl LatencyWindow {
+    pub(crate) fn new() -> Self {
+        Self {
+            samples: Mutex::new(VecDeque::with_capacity(STATS_WINDOW)),
+        }
+    }
+
+    /// Record a request, dropping the oldest one if the window is full
+    pub(crate) fn record(&self, latency: Duration, success: bool) {
+        let mut samples = self.samples.lock().unwrap();
+
+        if samples.len() >= STATS_WINDOW {
+            samples.pop_front();
+        }
+
+        samples.push_back((latency, success));
+    }
+
+    /// Summarize the recorded requests
+    pub(crate) fn summary(&self) -> TrackerStats {
+        let samples = self.samples.lock().unwrap();
+        let count = samples.len();
+
+        if count == 0 {
+            return TrackerStats::default();
+        }
+
+        let mut latencies = samples
+            .iter()
+            .map(|(latency, _)| *latency)
+            .collect::<Vec<_>>();
+        let errors = samples.iter().filter(|(_, success)| !success).count();
+
+        latencies.sort_unstable();
+
+        TrackerStats {
+            count,
+            p50: Some(percentile(&latencies, 0.5)),
+            p95: Some(percentile(&latencies, 0.95)),
+            error_rate: errors as f64 / count as f64,
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty latencies
+fn percentile(latencies: &[Duration], p: f64) -> Duration {
+    let rank = (p * latencies.len() as f64).ceil() as usize;
+
+    latencies[rank.clamp(1, latencies.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_summarize_latencies() {
+        let window = LatencyWindow::new();
+
+        assert_eq!(window.summary(), TrackerStats::default());
+
+        for ms in 1..=100 {
+            window.record(Duration::from_millis(ms), ms % 10 != 0);
+        }
+
+        let stats = window.summary();
+
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.p50, Some(Duration::from_millis(50)));
+        assert_eq!(stats.p95, Some(Duration::from_millis(95)));
+        assert!((stats.error_rate - 0.1).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn window_is_bounded() {
+        let window = LatencyWindow::new();
+
+        for _ in 0..STATS_WINDOW {
+            window.record(Duration::from_secs(1), false);
+        }
+
+        for _ in 0..STATS_WINDOW {
+            window.record(Duration::from_millis(1), true);
+        }
+
+        let stats = window.summary();
+
+        assert_eq!(stats.count, STATS_WINDOW);
+        assert_eq!(stats.p95, Some(Duration::from_millis(1)));
+        assert_eq!(stats.error_rate, 0.0);
+    }
+}
@@ -0,0 +1,183 @@
+//! Offline test support, available with the `test-util` feature.
+//! [MockTransport] records payloads instead of sending them, so tests run without credentials.
+
+use crate::sdk::builder::INERT_API_URL;
+use crate::sdk::{Tracker, transport};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Transport recording every payload and answering with queued responses, `200` once the
+/// queue is empty. Clones share the recorded payloads, so keep a clone to inspect them.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    payloads: Arc<Mutex<Vec<Value>>>,
+    responses: Arc<Mutex<VecDeque<(u16, String)>>>,
+}
+
+impl MockTransport {
+    /// Create new transport answering every request with `200`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a response for the next request not answered yet
+    pub fn with_response(self, status: u16, body: impl Into<String>) -> Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .push_back((status, body.into()));
+        self
+    }
+
+    /// Create a tracker with default headers sending via this transport. Its API URL is
+    /// [INERT_API_URL], which is never contacted.
+    pub fn tracker(&self) -> Tracker {
+        Tracker::new(
+            INERT_API_URL.to_string(),
+            "client_id".to_string(),
+            "client_secret".to_string(),
+        )
+        .with_default_headers()
+        .expect("valid default headers")
+        .with_transport(self.clone())
+    }
+
+    /// All payloads received so far, oldest first
+    pub fn payloads(&self) -> Vec<Value> {
+        self.payloads.lock().unwrap().clone()
+    }
+
+    /// Names of all `track` events received so far, oldest first
+    pub fn events(&self) -> Vec<String> {
+        self.payloads
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|payload| payload["type"] == "track")
+            .filter_map(|payload| payload["payload"]["name"].as_str())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Forget all payloads received so far
+    pub fn clear(&self) {
+        self.payloads.lock().unwrap().clear();
+    }
+
+    /// Return the latest `track` payload of the event, panicking if it wasn't received
+    #[track_caller]
+    pub fn assert_tracked(&self, event: &str) -> Value {
+        self.payloads()
+            .into_iter()
+            .rev()
+            .find(|payload| payload["type"] == "track" && payload["payload"]["name"] == event)
+            .unwrap_or_else(|| panic!("event {} not tracked, got {:?}", event, self.events()))
+    }
+
+    /// Panic unless a payload containing `expected` was received. Objects match if they
+    /// contain all fields of `expected`, other values have to be equal.
+    #[track_caller]
+    pub fn assert_received(&self, expected: &Value) {
+        let payloads = self.payloads();
+
+        if !payloads.iter().any(|payload| contains(payload, expected)) {
+            panic!("no payload contains {}, got {:?}", expected, payloads);
+        }
+    }
+}
+
+impl transport::HttpTransport for MockTransport {
+    fn send(&self, request: http::Request<Vec<u8>>) -> transport::TransportFuture<'_> {
+        let payload = serde_json::from_slice(request.body()).unwrap_or(Value::Null);
+
+        self.payloads.lock().unwrap().push(payload);
+
+        let (status, body) = self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or((200, String::new()));
+
+        Box::pin(async move {
+            Ok(http::Response::builder()
+                .status(status)
+                .body(body.into_bytes())?)
+        })
+    }
+}
+
+/// Whether `actual` contains all fields of `expected`, recursing into objects
+fn contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected.iter().all(|(key, value)| {
+            actual
+                .get(key)
+                .is_some_and(|actual| contains(actual, value))
+        }),
+        _ => actual == expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    use serde_json::json;
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[tokio::test]
+    async fn can_assert_received_payloads() -> anyhow::Result<()> {
+        let transport = MockTransport::new();
+        let tracker = transport.tracker();
+
+        tracker
+            .track(
+                "signup".to_string(),
+                Some("rust_123".to_string()),
+                None,
+                None,
+            )
+            .await?;
+        tracker
+            .increment("rust_123".to_string(), "visits".to_string(), 1)
+            .await?;
+
+        assert_eq!(transport.events(), vec!["signup"]);
+        assert_eq!(
+            transport.assert_tracked("signup")["payload"]["profileId"],
+            "rust_123"
+        );
+        transport.assert_received(&json!({"type": "increment", "payload": {"value": 1}}));
+
+        transport.clear();
+
+        assert!(transport.payloads().is_empty());
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "disabled-at-compile-time"))]
+    #[tokio::test]
+    async fn answers_with_queued_responses() -> anyhow::Result<()> {
+        let transport = MockTransport::new().with_response(400, "invalid");
+        let tracker = transport.tracker();
+        let response = tracker.track("test".to_string(), None, None, None).await?;
+
+        assert_eq!(response.status(), 400);
+        assert_eq!(response.text().await?, "invalid");
+
+        let response = tracker.track("test".to_string(), None, None, None).await?;
+
+        assert_eq!(response.status(), 200);
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "event login not tracked")]
+    fn panics_if_event_is_missing() {
+        MockTransport::new().assert_tracked("login");
+    }
+}
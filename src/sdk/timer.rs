@@ -0,0 +1,100 @@
+//! Timer used to track durations
+
+use crate::TrackerResult;
+use crate::sdk::Tracker;
+use reqwest::Response;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Property name used for the measured duration in milliseconds
+pub const DURATION_PROPERTY: &str = "duration_ms";
+
+/// Running timer created by [Tracker::start_timer]
+#[derive(Debug)]
+pub struct Timer<'a> {
+    tracker: &'a Tracker,
+    event: String,
+    started: Instant,
+}
+
+impl<'a> Timer<'a> {
+    pub(crate) fn new(tracker: &'a Tracker, event: String) -> Self {
+        Self {
+            tracker,
+            event,
+            started: tracker.clock.now(),
+        }
+    }
+
+    /// Time passed since the timer was started
+    pub fn elapsed(&self) -> Duration {
+        self.tracker
+            .clock
+            .now()
+            .saturating_duration_since(self.started)
+    }
+
+    /// Stop the timer and track the event with the elapsed time in milliseconds as
+    /// [DURATION_PROPERTY] property
+    pub async fn finish(
+        self,
+        profile_id: Option<String>,
+        properties: Option<HashMap<String, String>>,
+    ) -> TrackerResult<Response> {
+        let properties = self.create_properties(properties);
+
+        self.tracker
+            .track_unfiltered(self.event, profile_id, Some(properties))
+            .await
+    }
+
+    /// Add the elapsed time to the given properties
+    fn create_properties(
+        &self,
+        properties: Option<HashMap<String, String>>,
+    ) -> HashMap<String, String> {
+        let mut properties = properties.unwrap_or_default();
+
+        properties.insert(
+            DURATION_PROPERTY.to_string(),
+            self.elapsed().as_millis().to_string(),
+        );
+
+        properties
+    }
+}
+
+impl Tracker {
+    /// Start a timer for the given event. Call [Timer::finish] to track the event with the
+    /// elapsed time.
+    pub fn start_timer(&self, event: &str) -> Timer<'_> {
+        Timer::new(self, event.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::clock::ManualClock;
+    use crate::sdk::testing::MockTransport;
+
+    #[test]
+    fn can_add_duration_to_properties() -> anyhow::Result<()> {
+        let clock = ManualClock::default();
+        let tracker = MockTransport::new().tracker().with_clock(clock.clone());
+        let timer = tracker.start_timer("page_load");
+
+        clock.advance(Duration::from_millis(5));
+
+        let properties = timer.create_properties(Some(HashMap::from([(
+            "page".to_string(),
+            "home".to_string(),
+        )])));
+        let duration = properties.get(DURATION_PROPERTY).unwrap().parse::<u128>()?;
+
+        assert_eq!(duration, 5);
+        assert_eq!(properties.get("page").unwrap(), "home");
+
+        Ok(())
+    }
+}
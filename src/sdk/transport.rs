@@ -0,0 +1,58 @@
+//! Pluggable HTTP transport used to send events
+
+use crate::TrackerResult;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Future returned by [HttpTransport::send]
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = TrackerResult<http::Response<Vec<u8>>>> + Send + 'a>>;
+
+/// HTTP client used to send events, set via [crate::sdk::Tracker::with_transport].
+/// Implement this to use your own HTTP client on targets without reqwest, or to mock the
+/// send path in tests. Without a custom transport, events are sent via the tracker's
+/// [reqwest::Client], which implements this trait.
+///
+/// Return failures of your client as [crate::TrackerError::Transport], so retries and the
+/// replay buffer can tell connection failures and timeouts from other errors.
+pub trait HttpTransport: Send + Sync {
+    /// Send the request, carrying the method, API URL, headers and body serialized by the
+    /// tracker's [crate::sdk::serializer::PayloadSerializer], and return the response
+    fn send(&self, request: http::Request<Vec<u8>>) -> TransportFuture<'_>;
+}
+
+impl HttpTransport for reqwest::Client {
+    fn send(&self, request: http::Request<Vec<u8>>) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let response = self.execute(request.try_into()?).await?;
+            let mut builder = http::Response::builder()
+                .status(response.status())
+                .version(response.version());
+
+            if let Some(headers) = builder.headers_mut() {
+                headers.extend(response.headers().clone());
+            }
+
+            let body = response.bytes().await?.to_vec();
+
+            // status and headers are taken from a valid response
+            Ok(builder.body(body).expect("valid response"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reqwest_transport_returns_errors() {
+        let request = http::Request::builder()
+            .uri("http://127.0.0.1:9/track")
+            .body(Vec::new())
+            .unwrap();
+        let result = reqwest::Client::new().send(request).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -1,6 +1,8 @@
 //! Tracking user used for identify user calls
 
-use serde::Serialize;
+use crate::TrackerResult;
+use crate::sdk::{Tracker, ensure_success};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// User object used for identify user calls
@@ -13,3 +15,122 @@ pub struct IdentifyUser {
     pub last_name: String,
     pub properties: HashMap<String, String>,
 }
+
+/// Outcome of [Tracker::identify_resolved]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentifyResponse {
+    /// Profile id confirmed by OpenPanel, e.g. the canonical id after profiles were merged.
+    /// The id sent if the response doesn't include one.
+    pub profile_id: String,
+    /// Whether the profile id was returned by OpenPanel
+    pub confirmed: bool,
+}
+
+/// Body of an identify response, e.g. `{"merged":true,"profileId":"user_123"}`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IdentifyResponseBody {
+    profile_id: Option<String>,
+}
+
+impl IdentifyResponse {
+    /// Parse the profile id from the response body, falling back to the id sent if the body
+    /// is empty, not JSON or doesn't include a profile id
+    pub fn from_body(body: &str, sent_profile_id: String) -> Self {
+        let confirmed = serde_json::from_str::<IdentifyResponseBody>(body)
+            .ok()
+            .and_then(|body| body.profile_id)
+            .filter(|profile_id| !profile_id.is_empty());
+
+        match confirmed {
+            Some(profile_id) => Self {
+                profile_id,
+                confirmed: true,
+            },
+            None => Self {
+                profile_id: sent_profile_id,
+                confirmed: false,
+            },
+        }
+    }
+}
+
+impl Tracker {
+    /// Identify user like [Tracker::identify] and return the profile id confirmed by OpenPanel,
+    /// so the canonical id can be stored instead of a possibly stale local one. Non-2xx
+    /// responses are returned as errors, see [crate::sdk::ensure_success].
+    pub async fn identify_resolved(&self, user: IdentifyUser) -> TrackerResult<IdentifyResponse> {
+        let profile_id = user.profile_id.clone();
+        let body = ensure_success(self.identify(user).await?)?.text().await?;
+
+        Ok(IdentifyResponse::from_body(&body, profile_id))
+    }
+}
+
+#[cfg(all(test, not(feature = "disabled-at-compile-time")))]
+mod tests {
+    use super::*;
+    use crate::TrackerError;
+    use crate::sdk::testing::MockTransport;
+
+    fn user(profile_id: &str) -> IdentifyUser {
+        IdentifyUser {
+            profile_id: profile_id.to_string(),
+            email: "user@example.com".to_string(),
+            first_name: "Jane".to_string(),
+            last_name: "Doe".to_string(),
+            properties: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_canonical_profile_id_of_merged_profiles() -> anyhow::Result<()> {
+        let transport = MockTransport::new().with_response(
+            200,
+            r#"{"merged":true,"profileId":"user_canonical","mergedFrom":["anon_123"]}"#,
+        );
+        let response = transport
+            .tracker()
+            .identify_resolved(user("anon_123"))
+            .await?;
+
+        assert_eq!(
+            response,
+            IdentifyResponse {
+                profile_id: "user_canonical".to_string(),
+                confirmed: true
+            }
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_sent_profile_id() -> anyhow::Result<()> {
+        let transport = MockTransport::new()
+            .with_response(200, "")
+            .with_response(200, "OK")
+            .with_response(200, r#"{"deviceId":"device_123"}"#);
+        let tracker = transport.tracker();
+
+        for _ in 0..3 {
+            let response = tracker.identify_resolved(user("user_123")).await?;
+
+            assert_eq!(response.profile_id, "user_123");
+            assert!(!response.confirmed);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rejected_identifies_are_errors() {
+        let transport = MockTransport::new().with_response(401, "");
+        let result = transport
+            .tracker()
+            .identify_resolved(user("user_123"))
+            .await;
+
+        assert!(matches!(result, Err(TrackerError::NotAuthorized)));
+    }
+}
@@ -1,4 +1,8 @@
-use openpanel_sdk::sdk::Tracker;
+// Every test sends a request, which builds without telemetry never do
+#![cfg(not(feature = "disabled-at-compile-time"))]
+
+use openpanel_sdk::sdk::testing::MockTransport;
+use serde_json::json;
 use std::collections::HashMap;
 
 fn get_profile_id() -> Option<String> {
@@ -9,8 +13,9 @@ fn get_profile_id() -> Option<String> {
 async fn can_track_event() -> anyhow::Result<()> {
     let global_properties = HashMap::from([("global".to_string(), "property".to_string())]);
     let local_properties = HashMap::from([("local".to_string(), "property".to_string())]);
-    let tracker = Tracker::try_new_from_env()?
-        .with_default_headers()?
+    let transport = MockTransport::new();
+    let tracker = transport
+        .tracker()
         .with_global_properties(global_properties);
     let response = tracker
         .track(
@@ -22,6 +27,9 @@ async fn can_track_event() -> anyhow::Result<()> {
         .await?;
 
     assert_eq!(response.status(), 200);
+    transport.assert_received(&json!({
+        "payload": {"properties": {"global": "property", "local": "property"}}
+    }));
 
     Ok(())
 }
@@ -1,4 +1,9 @@
-use openpanel_sdk::sdk::{user, Tracker};
+// Every test sends a request, which builds without telemetry never do
+#![cfg(not(feature = "disabled-at-compile-time"))]
+
+use openpanel_sdk::sdk::testing::MockTransport;
+use openpanel_sdk::sdk::user;
+use serde_json::json;
 use std::collections::HashMap;
 
 struct Address {
@@ -52,10 +57,14 @@ async fn can_identify_user() -> anyhow::Result<()> {
             zip: "12345".to_string(),
         },
     };
-    let tracker = Tracker::try_new_from_env()?.with_default_headers()?;
-    let response = tracker.identify(user.into()).await?;
+    let transport = MockTransport::new();
+    let response = transport.tracker().identify(user.into()).await?;
 
     assert_eq!(response.status(), 200);
+    transport.assert_received(&json!({
+        "type": "identify",
+        "payload": {"profileId": "test_profile_id", "properties": {"city": "London"}}
+    }));
 
     Ok(())
 }
@@ -1,13 +1,19 @@
-use openpanel_sdk::sdk::{user, Tracker};
+use openpanel_sdk::sdk::testing::MockTransport;
+#[cfg(not(feature = "disabled-at-compile-time"))]
+use openpanel_sdk::sdk::user;
+#[cfg(not(feature = "disabled-at-compile-time"))]
+use serde_json::json;
 use std::collections::HashMap;
 
 fn get_profile_id() -> Option<String> {
     Some("rust_123123123".to_string())
 }
 
+#[cfg(not(feature = "disabled-at-compile-time"))]
 #[tokio::test]
 async fn can_track_event() -> anyhow::Result<()> {
-    let tracker = Tracker::try_new_from_env()?.with_default_headers()?;
+    let transport = MockTransport::new();
+    let tracker = transport.tracker();
     let mut properties = HashMap::new();
 
     properties.insert("name".to_string(), "rust".to_string());
@@ -22,13 +28,16 @@ async fn can_track_event() -> anyhow::Result<()> {
         .await?;
 
     assert_eq!(response.status(), 200);
+    assert_eq!(transport.events(), vec!["test_event"]);
 
     Ok(())
 }
 
+#[cfg(not(feature = "disabled-at-compile-time"))]
 #[tokio::test]
 async fn can_apply_no_filter() -> anyhow::Result<()> {
-    let tracker = Tracker::try_new_from_env()?.with_default_headers()?;
+    let transport = MockTransport::new();
+    let tracker = transport.tracker();
     let mut properties = HashMap::new();
 
     properties.insert("name".to_string(), "rust".to_string());
@@ -43,6 +52,7 @@ async fn can_apply_no_filter() -> anyhow::Result<()> {
         .await?;
 
     assert_eq!(response.status(), 200);
+    transport.assert_received(&json!({"payload": {"properties": {"name": "rust"}}}));
 
     Ok(())
 }
@@ -50,7 +60,8 @@ async fn can_apply_no_filter() -> anyhow::Result<()> {
 #[tokio::test]
 async fn can_apply_filter_track_event() -> anyhow::Result<()> {
     let filter = |properties: HashMap<String, String>| properties.contains_key("name");
-    let tracker = Tracker::try_new_from_env()?.with_default_headers()?;
+    let transport = MockTransport::new();
+    let tracker = transport.tracker();
     let mut properties = HashMap::new();
 
     properties.insert("name".to_string(), "rust".to_string());
@@ -65,13 +76,16 @@ async fn can_apply_filter_track_event() -> anyhow::Result<()> {
         .await;
 
     assert!(response.is_err());
+    assert!(transport.payloads().is_empty());
 
     Ok(())
 }
 
+#[cfg(not(feature = "disabled-at-compile-time"))]
 #[tokio::test]
 async fn can_identify_user() -> anyhow::Result<()> {
-    let tracker = Tracker::try_new_from_env()?.with_default_headers()?;
+    let transport = MockTransport::new();
+    let tracker = transport.tracker();
     let mut properties = HashMap::new();
 
     properties.insert("name".to_string(), "rust".to_string());
@@ -87,13 +101,16 @@ async fn can_identify_user() -> anyhow::Result<()> {
     let response = tracker.identify(user).await?;
 
     assert_eq!(response.status(), 200);
+    transport.assert_received(&json!({"type": "identify", "payload": {"email": "rust@test.com"}}));
 
     Ok(())
 }
 
+#[cfg(not(feature = "disabled-at-compile-time"))]
 #[tokio::test]
 async fn can_increment_property() -> anyhow::Result<()> {
-    let tracker = Tracker::try_new_from_env()?.with_default_headers()?;
+    let transport = MockTransport::new();
+    let tracker = transport.tracker();
     let response = tracker
         .increment(
             "test_profile_id".to_string(),
@@ -103,13 +120,16 @@ async fn can_increment_property() -> anyhow::Result<()> {
         .await?;
 
     assert_eq!(response.status(), 200);
+    transport.assert_received(&json!({"type": "increment", "payload": {"value": 1}}));
 
     Ok(())
 }
 
+#[cfg(not(feature = "disabled-at-compile-time"))]
 #[tokio::test]
 async fn can_decrement_property() -> anyhow::Result<()> {
-    let tracker = Tracker::try_new_from_env()?.with_default_headers()?;
+    let transport = MockTransport::new();
+    let tracker = transport.tracker();
     let response = tracker
         .decrement(
             "test_profile_id".to_string(),
@@ -119,19 +139,25 @@ async fn can_decrement_property() -> anyhow::Result<()> {
         .await?;
 
     assert_eq!(response.status(), 200);
+    transport.assert_received(&json!({"type": "decrement", "payload": {"value": 1}}));
 
     Ok(())
 }
 
+#[cfg(not(feature = "disabled-at-compile-time"))]
 #[tokio::test]
 async fn can_track_revenue() -> anyhow::Result<()> {
-    let tracker = Tracker::try_new_from_env()?.with_default_headers()?;
+    let transport = MockTransport::new();
+    let tracker = transport.tracker();
     let properties = HashMap::from([("currency".to_string(), "EUR".to_string())]);
     let response = tracker
         .revenue(get_profile_id(), 100, Some(properties))
         .await?;
 
     assert_eq!(response.status(), 200);
+    transport.assert_received(&json!({
+        "payload": {"name": "revenue", "properties": {"__revenue": "100", "currency": "EUR"}}
+    }));
 
     Ok(())
 }